@@ -43,6 +43,11 @@ def buildin(domain):
         relist = []
         for item in jlist:
             item = json.loads(item)
+            if "schema_version" in item:
+                # Normalized schema line, see buildinraw/Deltachat/src/schema.rs
+                if item.get("source") != key:
+                    continue
+                item[key] = dict(item)
             if "domain" not in item or key not in item:
                 continue
                 
@@ -0,0 +1,48 @@
+//! Regenerates the JSON lines under `buildinlists/` from the raw client
+//! databases under `buildinraw/`.
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::{Context as _, Result};
+use clap::Parser;
+
+use deltachat::buildin::{write_list, Source};
+
+/// Regenerate the built-in lists from the raw client databases.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Cli {
+    /// The `buildinraw` directory.
+    #[arg(long, default_value = "..")]
+    raw_dir: PathBuf,
+
+    /// The `buildinlists` directory to write to.
+    #[arg(long, default_value = "../../buildinlists")]
+    out_dir: PathBuf,
+
+    /// Only regenerate these lists (may be repeated).
+    #[arg(short, long = "source", value_enum, value_name = "SOURCE")]
+    sources: Vec<Source>,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let sources = if cli.sources.is_empty() {
+        Source::ALL.to_vec()
+    } else {
+        cli.sources
+    };
+
+    for source in sources {
+        let entries = source.load(&cli.raw_dir)?;
+        let path = cli.out_dir.join(source.list_file());
+        let file = std::fs::File::create(&path)
+            .with_context(|| format!("cannot create {}", path.display()))?;
+        let mut out = std::io::BufWriter::new(file);
+        write_list(&mut out, &entries)?;
+        out.flush()?;
+        eprintln!("{}: {} entries", path.display(), entries.len());
+    }
+    Ok(())
+}
@@ -0,0 +1,66 @@
+//! Reports inconsistencies between the built-in lists.
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+
+use deltachat::consistency::Report;
+use deltachat::idn;
+use deltachat::output::{open_output, write_json};
+
+/// Output format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Human-readable summary table.
+    Table,
+
+    /// Machine-readable JSON.
+    Json,
+}
+
+/// Compare the entries of all built-in lists domain by domain.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Cli {
+    /// The `buildinlists` directory.
+    #[arg(long, default_value = "../../buildinlists")]
+    lists_dir: PathBuf,
+
+    /// File to write to, `-` for stdout.
+    #[arg(short, long, default_value = "-")]
+    output: PathBuf,
+
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Table)]
+    format: Format,
+
+    /// Only report these domains (may be repeated); `bücher.de` and
+    /// `xn--bcher-kva.de` are the same domain.
+    #[arg(short, long = "domain", value_name = "DOMAIN", value_parser = parse_domain)]
+    domains: Vec<String>,
+}
+
+/// Domains are compared in their ASCII form.
+fn parse_domain(domain: &str) -> Result<String, std::convert::Infallible> {
+    Ok(idn::normalize(domain))
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let mut report = Report::from_lists_dir(&cli.lists_dir)?;
+    if !cli.domains.is_empty() {
+        report
+            .domains
+            .retain(|d| cli.domains.contains(&idn::normalize(&d.domain)));
+    }
+
+    let mut out = open_output(&cli.output)?;
+    match cli.format {
+        Format::Table => report.write_table(&mut out)?,
+        Format::Json => write_json(&mut out, &report)?,
+    }
+    out.flush()?;
+    Ok(())
+}
@@ -0,0 +1,82 @@
+//! Grades the security of every entry of the built-in lists.
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::{Context as _, Result};
+use clap::Parser;
+use serde::Serialize;
+
+use deltachat::buildin::Source;
+use deltachat::grade::{EntryGrade, GradingRules};
+use deltachat::output::open_output;
+use deltachat::schema;
+
+/// One output line.
+#[derive(Debug, Serialize)]
+struct Line<'a> {
+    source: Source,
+    domain: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provider: Option<&'a str>,
+    security: EntryGrade,
+}
+
+/// Grade every entry of the built-in lists, writing JSON lines.
+///
+/// The lists carry no `strict_tls` option, so certificates are assumed to be
+/// checked strictly; use the `deltachat` exporter with `--grade` to take the
+/// Delta Chat provider options into account.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Cli {
+    /// The `buildinlists` directory.
+    #[arg(long, default_value = "../../buildinlists")]
+    lists_dir: PathBuf,
+
+    /// File to write to, `-` for stdout.
+    #[arg(short, long, default_value = "-")]
+    output: PathBuf,
+
+    /// YAML or JSON file overriding the default grading rules.
+    #[arg(long, value_name = "PATH")]
+    grading_rules: Option<PathBuf>,
+
+    /// Only grade these lists (may be repeated).
+    #[arg(short, long = "source", value_enum, value_name = "SOURCE")]
+    sources: Vec<Source>,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let rules = match &cli.grading_rules {
+        Some(path) => GradingRules::from_file(path)?,
+        None => GradingRules::default(),
+    };
+    let sources = if cli.sources.is_empty() {
+        Source::ALL.to_vec()
+    } else {
+        cli.sources
+    };
+
+    let mut out = open_output(&cli.output)?;
+    for source in sources {
+        let path = cli.lists_dir.join(source.list_file());
+        let src = std::fs::read_to_string(&path)
+            .with_context(|| format!("cannot read {}", path.display()))?;
+        let entries = schema::read_list(source, &src)
+            .with_context(|| format!("cannot parse {}", path.display()))?;
+        for entry in &entries {
+            let line = Line {
+                source,
+                domain: &entry.domain,
+                provider: entry.provider.as_deref(),
+                security: rules.grade_entry(entry, true),
+            };
+            serde_json::to_writer(&mut out, &line)?;
+            writeln!(out)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
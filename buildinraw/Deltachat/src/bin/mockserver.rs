@@ -0,0 +1,152 @@
+//! Serves autoconfiguration documents from the provider database on
+//! localhost, for offline end-to-end tests of the scanners.
+
+use std::collections::BTreeSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, Context as _, Result};
+use clap::Parser;
+
+use deltachat::mock::{Fault, Faults, Request, Responder};
+use deltachat::provider::ProviderDb;
+
+/// Serve autoconfig, Autodiscover and ISPDB documents for the providers of
+/// the Delta Chat database.
+///
+/// Point clients at the server by resolving `autoconfig.<domain>` and
+/// `autodiscover.<domain>` to the listen address, or map the address to a
+/// domain with `--map`.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Cli {
+    /// Address to listen on.
+    #[arg(short, long, default_value = "127.0.0.1:8080")]
+    listen: String,
+
+    /// Answer requests for HOST as if they were for DOMAIN (may be repeated).
+    #[arg(short, long = "map", value_name = "HOST=DOMAIN", value_parser = parse_mapping)]
+    mappings: Vec<(String, String)>,
+
+    /// Upstream `data.rs` to read instead of the bundled snapshot.
+    #[arg(long, value_name = "PATH", conflicts_with = "md_dir")]
+    data: Option<PathBuf>,
+
+    /// Directory of provider-db `.md` files to read instead of the bundled snapshot.
+    #[arg(long, value_name = "DIR")]
+    md_dir: Option<PathBuf>,
+
+    /// Misbehave this way (may be repeated).
+    #[arg(short, long = "fault", value_enum, value_name = "FAULT")]
+    faults: Vec<Fault>,
+
+    /// Host `foreign-redirect` redirects to.
+    #[arg(long, value_name = "HOST", default_value = "attacker.example")]
+    foreign_host: String,
+
+    /// Seconds `slow` waits before each response.
+    #[arg(long, value_name = "SECS", default_value_t = 30)]
+    delay: u64,
+}
+
+fn parse_mapping(s: &str) -> Result<(String, String), String> {
+    let (host, domain) = s
+        .split_once('=')
+        .ok_or_else(|| format!("expected HOST=DOMAIN, got {s:?}"))?;
+    Ok((host.to_string(), domain.to_string()))
+}
+
+/// Listens with a freshly generated self-signed certificate for `localhost`
+/// and the mapped hosts.
+fn https_server(cli: &Cli) -> Result<tiny_http::Server> {
+    let mut names = vec!["localhost".to_string()];
+    names.extend(cli.mappings.iter().map(|(host, _)| {
+        let name = host
+            .rsplit_once(':')
+            .map_or(host.as_str(), |(name, _)| name);
+        name.to_string()
+    }));
+    let cert = rcgen::generate_simple_self_signed(names).context("cannot generate certificate")?;
+    let ssl = tiny_http::SslConfig {
+        certificate: cert.serialize_pem()?.into_bytes(),
+        private_key: cert.serialize_private_key_pem().into_bytes(),
+    };
+    tiny_http::Server::https(&cli.listen, ssl)
+        .map_err(|err| anyhow!("cannot listen on {}: {err}", cli.listen))
+}
+
+fn handle(responder: &Responder, mut request: tiny_http::Request) -> Result<()> {
+    let mut body = Vec::new();
+    request
+        .as_reader()
+        .read_to_end(&mut body)
+        .context("cannot read request body")?;
+    let host = request
+        .headers()
+        .iter()
+        .find(|h| h.field.equiv("Host"))
+        .map(|h| h.value.as_str().to_string());
+    let method = request.method().as_str().to_string();
+    let response = responder.respond(&Request {
+        method: &method,
+        url: request.url(),
+        host: host.as_deref(),
+        body: &body,
+    });
+    eprintln!(
+        "{} {} {} -> {}",
+        host.as_deref().unwrap_or("-"),
+        method,
+        request.url(),
+        response.status
+    );
+
+    let mut reply = tiny_http::Response::from_data(response.body).with_status_code(response.status);
+    for (field, value) in &response.headers {
+        let header = tiny_http::Header::from_bytes(field.as_bytes(), value.as_bytes())
+            .map_err(|()| anyhow!("invalid header {field}: {value}"))?;
+        reply.add_header(header);
+    }
+    std::thread::sleep(response.delay);
+    request.respond(reply).context("cannot send response")
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let db = match (&cli.data, &cli.md_dir) {
+        (Some(path), _) => ProviderDb::from_data_rs_file(path)?,
+        (None, Some(dir)) => ProviderDb::from_md_dir(dir)?,
+        (None, None) => ProviderDb::bundled(),
+    };
+    let mut responder = Responder::new(db);
+    for (host, domain) in &cli.mappings {
+        responder.map_host(host, domain);
+    }
+    responder.set_faults(Faults {
+        enabled: cli.faults.iter().copied().collect::<BTreeSet<_>>(),
+        foreign_host: cli.foreign_host.clone(),
+        delay: Duration::from_secs(cli.delay),
+    });
+    let responder = Arc::new(responder);
+
+    let (server, scheme) = if cli.faults.contains(&Fault::SelfSigned) {
+        (https_server(&cli)?, "https")
+    } else {
+        let server = tiny_http::Server::http(&cli.listen)
+            .map_err(|err| anyhow!("cannot listen on {}: {err}", cli.listen))?;
+        (server, "http")
+    };
+    eprintln!("listening on {scheme}://{}", cli.listen);
+
+    // One thread per request, so that slow responses do not hold up others.
+    for request in server.incoming_requests() {
+        let responder = Arc::clone(&responder);
+        std::thread::spawn(move || {
+            if let Err(err) = handle(&responder, request) {
+                eprintln!("{err:#}");
+            }
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,68 @@
+//! Compares two snapshots of the Delta Chat provider database.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+
+use deltachat::output::{open_output, write_json};
+use deltachat::provider::diff::SnapshotDiff;
+use deltachat::provider::ProviderDb;
+
+/// Output format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Human-readable summary, one change per line.
+    Summary,
+
+    /// Machine-readable JSON.
+    Json,
+}
+
+/// Show what changed between two provider database snapshots.
+///
+/// Each snapshot is an upstream `data.rs` file or a directory of
+/// provider-db `.md` files; `bundled` stands for the bundled snapshot.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Cli {
+    /// Old snapshot.
+    old: PathBuf,
+
+    /// New snapshot.
+    new: PathBuf,
+
+    /// File to write to, `-` for stdout.
+    #[arg(short, long, default_value = "-")]
+    output: PathBuf,
+
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Summary)]
+    format: Format,
+}
+
+fn load(path: &Path) -> Result<ProviderDb> {
+    if path.as_os_str() == "bundled" {
+        Ok(ProviderDb::bundled())
+    } else if path.is_dir() {
+        ProviderDb::from_md_dir(path)
+    } else {
+        ProviderDb::from_data_rs_file(path)
+    }
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let old = load(&cli.old)?;
+    let new = load(&cli.new)?;
+    let diff = SnapshotDiff::new(&old, &new);
+
+    let mut out = open_output(&cli.output)?;
+    match cli.format {
+        Format::Summary => diff.write_summary(&mut out)?,
+        Format::Json => write_json(&mut out, &diff)?,
+    }
+    out.flush()?;
+    Ok(())
+}
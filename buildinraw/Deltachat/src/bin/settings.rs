@@ -0,0 +1,68 @@
+//! Reports the effective client settings of every provider.
+
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Parser, ValueEnum};
+
+use deltachat::output::{open_output, write_json};
+use deltachat::provider::ProviderDb;
+use deltachat::settings::Matrix;
+
+/// Output format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    /// Providers × keys matrix, overridden values marked with `*`.
+    Csv,
+
+    /// Machine-readable JSON, with keys and values as in the CSV.
+    Json,
+}
+
+/// Compute the effective value of every config key for every provider: the
+/// built-in default, overridden by the provider's `config_defaults`.
+#[derive(Debug, Parser)]
+#[command(version)]
+struct Cli {
+    /// File to write to, `-` for stdout.
+    #[arg(short, long, default_value = "-")]
+    output: PathBuf,
+
+    /// Output format.
+    #[arg(short, long, value_enum, default_value_t = Format::Csv)]
+    format: Format,
+
+    /// Upstream `data.rs` to read instead of the bundled snapshot.
+    #[arg(long, value_name = "PATH", conflicts_with = "md_dir")]
+    data: Option<PathBuf>,
+
+    /// Directory of provider-db `.md` files to read instead of the bundled snapshot.
+    #[arg(long, value_name = "DIR")]
+    md_dir: Option<PathBuf>,
+
+    /// Only report providers overriding at least one default.
+    #[arg(long)]
+    overridden: bool,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let db = match (&cli.data, &cli.md_dir) {
+        (Some(path), _) => ProviderDb::from_data_rs_file(path)?,
+        (None, Some(dir)) => ProviderDb::from_md_dir(dir)?,
+        (None, None) => ProviderDb::bundled(),
+    };
+    let mut matrix = Matrix::new(&db);
+    if cli.overridden {
+        matrix.rows.retain(|row| row.overrides_any());
+    }
+
+    let mut out = open_output(&cli.output)?;
+    match cli.format {
+        Format::Csv => matrix.write_csv(&mut out)?,
+        Format::Json => write_json(&mut out, &matrix)?,
+    }
+    out.flush()?;
+    Ok(())
+}
@@ -0,0 +1,290 @@
+//! Built-in client lists.
+//!
+//! Every client ships its own provider database in its own format. The
+//! submodules read those raw databases into the [`schema`](crate::schema)
+//! entries written as the JSON lines under `buildinlists/` that `buildin.py`
+//! reads.
+
+pub mod deltachat;
+pub mod fairemail;
+pub mod mailcore;
+pub mod mailspring;
+pub mod nodemailer;
+
+use std::io::Write;
+use std::path::Path;
+use std::sync::LazyLock;
+
+use anyhow::{Context as _, Result};
+use clap::ValueEnum;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::idn;
+use crate::provider::Socket;
+use crate::psl;
+use crate::schema::Entry;
+
+/// Client whose built-in list an entry comes from.
+///
+/// Serialized as its [`key`](Source::key).
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, ValueEnum, Serialize, Deserialize,
+)]
+pub enum Source {
+    /// Delta Chat provider database.
+    #[serde(rename = "DeltaChat")]
+    DeltaChat,
+
+    /// Nodemailer `services.json`.
+    #[serde(rename = "nodemailer")]
+    Nodemailer,
+
+    /// Mailspring `mailspring-provider-settings.json`.
+    #[serde(rename = "mailspring")]
+    Mailspring,
+
+    /// FairEmail `providers.xml`.
+    #[serde(rename = "FairEmail")]
+    FairEmail,
+
+    /// MailCore2 `providers.json`.
+    #[serde(rename = "mailcore")]
+    Mailcore,
+}
+
+impl Source {
+    pub const ALL: [Source; 5] = [
+        Source::DeltaChat,
+        Source::Nodemailer,
+        Source::Mailspring,
+        Source::FairEmail,
+        Source::Mailcore,
+    ];
+
+    /// Key of the list in `key.json` and in each JSON line.
+    pub fn key(self) -> &'static str {
+        match self {
+            Source::DeltaChat => "DeltaChat",
+            Source::Nodemailer => "nodemailer",
+            Source::Mailspring => "mailspring",
+            Source::FairEmail => "FairEmail",
+            Source::Mailcore => "mailcore",
+        }
+    }
+
+    /// File name of the list under `buildinlists/`.
+    pub fn list_file(self) -> &'static str {
+        match self {
+            Source::DeltaChat => "deltaChat.txt",
+            Source::Nodemailer => "nodemailer.txt",
+            Source::Mailspring => "mailspring-provider-settings.txt",
+            Source::FairEmail => "fairEmail.txt",
+            Source::Mailcore => "mailcore-provider-settings.txt",
+        }
+    }
+
+    /// Path of the raw client database under `buildinraw/`.
+    pub fn raw_file(self) -> &'static str {
+        match self {
+            Source::DeltaChat => "Deltachat/src/data.rs",
+            Source::Nodemailer => "Nodemailer/services.json",
+            Source::Mailspring => "Mailspring/mailspring-provider-settings.json",
+            Source::FairEmail => "FairEmail/providers.xml",
+            Source::Mailcore => "Mailcore2/mailcore-provider-settings.json",
+        }
+    }
+
+    /// Upstream commit the raw database under `buildinraw/` was taken from.
+    pub fn revision(self) -> &'static str {
+        match self {
+            Source::DeltaChat => "137e32fe49bc51a0602b158fc9e8a0df054384d3",
+            Source::Nodemailer => "d1ae0a86883ba6011a49a5bbdf076098e2e3637a",
+            Source::Mailspring => "17aa64165577c6bb794a13f6f2ddd19556c4ecc1",
+            Source::FairEmail => "be474a7aa3dedd695d29152dca305e4c9f8b03e6",
+            Source::Mailcore => "7417b2e8dd7e2c028aadb72056e4d1428c0627c4",
+        }
+    }
+
+    /// Parses the raw database of this client.
+    ///
+    /// Delta Chat entries carry the `_PROVIDER_UPDATED` date of the parsed
+    /// database as their revision; the other lists have no revision of their
+    /// own, so none is set. Internationalized domains get their Unicode
+    /// form.
+    pub fn parse(self, src: &str) -> Result<Vec<Entry>> {
+        let mut entries = match self {
+            Source::DeltaChat => deltachat::parse(src),
+            Source::Nodemailer => nodemailer::parse(src),
+            Source::Mailspring => mailspring::parse(src),
+            Source::FairEmail => fairemail::parse(src),
+            Source::Mailcore => mailcore::parse(src),
+        }?;
+        for entry in &mut entries {
+            entry.domain_unicode = idn::unicode_if_different(&entry.domain);
+        }
+        Ok(entries)
+    }
+
+    /// Reads and parses the raw database of this client from `raw_dir`.
+    ///
+    /// Entries other than Delta Chat ones get the pinned upstream
+    /// [`revision`](Source::revision) as their source revision.
+    pub fn load(self, raw_dir: &Path) -> Result<Vec<Entry>> {
+        let path = raw_dir.join(self.raw_file());
+        let src = std::fs::read_to_string(&path)
+            .with_context(|| format!("cannot read {}", path.display()))?;
+        let mut entries = self
+            .parse(&src)
+            .with_context(|| format!("cannot parse {}", path.display()))?;
+        if self != Source::DeltaChat {
+            for entry in &mut entries {
+                entry.source_revision = Some(self.revision().to_string());
+            }
+        }
+        Ok(entries)
+    }
+}
+
+/// Writes entries as JSON lines.
+pub fn write_list(out: &mut dyn Write, entries: &[Entry]) -> Result<()> {
+    for entry in entries {
+        out.write_all(entry.to_json().as_bytes())?;
+        out.write_all(b"\n")?;
+    }
+    Ok(())
+}
+
+/// Domain name pattern used by the Python converters to tell domains from
+/// provider names.
+static DOMAIN_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(([a-zA-Z]{1})|([a-zA-Z]{1}[a-zA-Z]{1})|([a-zA-Z]{1}[0-9]{1})|([0-9]{1}[a-zA-Z]{1})|([a-zA-Z0-9][-_.a-zA-Z0-9]{0,61}[a-zA-Z0-9]))\.([a-zA-Z]{2,13}|[a-zA-Z0-9-]{2,30}.[a-zA-Z]{2,3})$",
+    )
+    .unwrap()
+});
+
+/// Returns true if `name` looks like a domain rather than a provider name.
+pub fn is_domain_name(name: &str) -> bool {
+    DOMAIN_RE.is_match(name)
+}
+
+/// Registered domain of a server hostname, for lists that key entries by
+/// provider name. Falls back to the hostname if it is a public suffix.
+fn registered_domain(hostname: &str) -> String {
+    psl::registered_domain(hostname).unwrap_or_else(|| hostname.to_lowercase())
+}
+
+/// Reads a port given either as a number or as a string.
+fn port(value: &serde_json::Value) -> Option<u16> {
+    match value {
+        serde_json::Value::Number(n) => n.as_u64()?.try_into().ok(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+/// Socket security implied by a port when the list does not state it.
+fn socket_for_port(port: u16) -> Socket {
+    match port {
+        465 | 993 | 995 => Socket::Ssl,
+        _ => Socket::Plain,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::ProviderDb;
+    use crate::schema::Authentication;
+
+    #[test]
+    fn test_load_raw_lists() {
+        let raw_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        for (source, count) in [
+            (Source::DeltaChat, 318),
+            (Source::Nodemailer, 92),
+            (Source::Mailspring, 862),
+            (Source::FairEmail, 148),
+            (Source::Mailcore, 31),
+        ] {
+            assert_eq!(source.load(&raw_dir).unwrap().len(), count, "{source:?}");
+        }
+    }
+
+    #[test]
+    fn test_nodemailer_socket() {
+        let entries = nodemailer::parse(
+            r#"{"Plain": {"host": "smtp.plain.example", "port": 25, "domains": ["plain.example"]},
+                "Sub": {"host": "smtp.sub.example", "port": "587", "secure": false}}"#,
+        )
+        .unwrap();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[1].domain, "plain.example");
+        assert_eq!(entries[1].outgoing_servers[0].socket_type, Socket::Plain);
+        assert_eq!(entries[2].outgoing_servers[0].socket_type, Socket::Starttls);
+    }
+
+    #[test]
+    fn test_fairemail_provider_name() {
+        let entries = fairemail::parse(
+            r#"<providers><provider name="Gmail" domain="gmail\\.com,googlemail\\.com">
+                <imap host="imap.gmail.com" port="993" starttls="false" />
+                <smtp host="smtp.gmail.com" port="587" starttls="true" />
+                <oauth enabled="true" /></provider></providers>"#,
+        )
+        .unwrap();
+        let entry = &entries[0];
+        assert_eq!(entry.domain, "gmail.com");
+        assert_eq!(entry.provider.as_deref(), Some("Gmail"));
+        assert_eq!(
+            entry.domain_regular,
+            vec![r"gmail\.com", r"googlemail\.com"]
+        );
+        assert_eq!(entry.outgoing_servers[0].socket_type, Socket::Starttls);
+        assert_eq!(
+            entry.incoming_servers[0].authentication,
+            vec![Authentication::OAuth2]
+        );
+        assert_eq!(entry.oauth2, None);
+    }
+
+    #[test]
+    fn test_oauth2_descriptors() {
+        let entries = fairemail::parse(
+            r#"<providers><provider name="outlook.com">
+                <imap host="outlook.office365.com" port="993" />
+                <oauth enabled="true" clientId="id" redirectUri="https://oauth.example/"
+                    authorizationEndpoint="https://login.example/authorize"
+                    tokenEndpoint="https://login.example/token" scopes="openid,IMAP email">
+                    <parameter key="prompt" value="login" /></oauth></provider>
+                <provider name="163.com"><imap host="imap.163.com" port="993" />
+                <oauth enabled="false" authorizationEndpoint="https://163.example/a"
+                    tokenEndpoint="https://163.example/t" /></provider></providers>"#,
+        )
+        .unwrap();
+        let oauth2 = entries[0].oauth2.as_ref().unwrap();
+        assert_eq!(oauth2.token_endpoint, "https://login.example/token");
+        assert_eq!(oauth2.scopes, ["openid", "IMAP", "email"]);
+        assert_eq!(oauth2.client_id.as_deref(), Some("id"));
+        assert_eq!(
+            oauth2.redirect_uri.as_deref(),
+            Some("https://oauth.example/")
+        );
+        assert_eq!(oauth2.parameters["prompt"], "login");
+        assert_eq!(entries[1].oauth2, None);
+
+        let db = ProviderDb::bundled();
+        let gmail = deltachat::entries(&db)
+            .into_iter()
+            .find(|e| e.domain == "gmail.com")
+            .unwrap();
+        assert_eq!(
+            gmail.incoming_servers[0].authentication,
+            [Authentication::OAuth2, Authentication::PasswordCleartext]
+        );
+        let oauth2 = gmail.oauth2.unwrap();
+        assert_eq!(oauth2.scopes, ["https://mail.google.com/", "email"]);
+        assert_eq!(oauth2.redirect_uri, None);
+    }
+}
@@ -0,0 +1,48 @@
+//! Delta Chat provider database.
+
+use anyhow::Result;
+
+use super::Source;
+use crate::idn;
+use crate::provider::{Direction, Oauth2Authorizer, Provider, ProviderDb, Server};
+use crate::schema::{Entry, MailServer};
+
+fn modify(x: &Server, info: &Provider) -> MailServer {
+    MailServer {
+        protocol: x.protocol,
+        hostname: x.hostname.clone(),
+        port: x.port,
+        socket_type: x.socket,
+        authentication: info.authentication(),
+        username: Some(x.username_pattern.placeholder().to_string()),
+    }
+}
+
+/// Builds the entry of one `PROVIDER_DATA` domain, splitting the servers of
+/// its provider into incoming and outgoing ones.
+pub fn entry(db: &ProviderDb, domain: &str, info: &Provider) -> Entry {
+    let mut re = Entry::new(Source::DeltaChat, domain);
+    re.source_revision = db.updated().map(str::to_string);
+    re.domain_unicode = idn::unicode_if_different(domain);
+    for j in &info.server {
+        let cur = modify(j, info);
+        match cur.protocol.direction() {
+            Direction::Incoming => re.incoming_servers.push(cur),
+            Direction::Outgoing => re.outgoing_servers.push(cur),
+        }
+    }
+    re.oauth2 = info.oauth2_authorizer.map(Oauth2Authorizer::descriptor);
+    re
+}
+
+/// One entry per domain in `PROVIDER_DATA`.
+pub fn entries(db: &ProviderDb) -> Vec<Entry> {
+    db.provider_data()
+        .map(|(domain, info)| entry(db, domain, info))
+        .collect()
+}
+
+/// Parses upstream `data.rs`.
+pub fn parse(src: &str) -> Result<Vec<Entry>> {
+    Ok(entries(&ProviderDb::from_data_rs(src)?))
+}
@@ -0,0 +1,138 @@
+//! FairEmail `providers.xml`.
+
+use anyhow::Result;
+use roxmltree::{Document, Node};
+
+use super::Source;
+use crate::provider::{Protocol, Socket};
+use crate::schema::{Authentication, Entry, MailServer, OAuth2Descriptor};
+
+fn getssl(starttls: Option<&str>, port: u16) -> Socket {
+    if starttls == Some("true") {
+        Socket::Starttls
+    } else {
+        super::socket_for_port(port)
+    }
+}
+
+/// Reads all `<imap>`, `<pop>` or `<smtp>` children of a provider.
+///
+/// Servers without a valid port are skipped.
+fn setservers(
+    provider: Node,
+    tag: &str,
+    protocol: Protocol,
+    auth: &[Authentication],
+) -> Vec<MailServer> {
+    provider
+        .children()
+        .filter(|n| n.has_tag_name(tag))
+        .filter_map(|ser| {
+            let port = ser.attribute("port")?.parse().ok()?;
+            Some(MailServer {
+                protocol,
+                hostname: ser.attribute("host").unwrap_or_default().to_string(),
+                port,
+                socket_type: getssl(ser.attribute("starttls"), port),
+                authentication: auth.to_vec(),
+                username: None,
+            })
+        })
+        .collect()
+}
+
+/// Reads an `<oauth>` element; `None` if it is disabled or lacks an
+/// endpoint.
+///
+/// Scopes are separated by commas or spaces.
+fn oauth2(oauth: Node) -> Option<OAuth2Descriptor> {
+    if oauth.attribute("enabled") == Some("false") {
+        return None;
+    }
+    let attribute = |name| {
+        oauth
+            .attribute(name)
+            .filter(|v| !v.is_empty())
+            .map(str::to_string)
+    };
+    Some(OAuth2Descriptor {
+        authorization_endpoint: attribute("authorizationEndpoint")?,
+        token_endpoint: attribute("tokenEndpoint")?,
+        scopes: oauth
+            .attribute("scopes")
+            .unwrap_or_default()
+            .split([',', ' '])
+            .filter(|s| !s.is_empty())
+            .map(str::to_string)
+            .collect(),
+        client_id: attribute("clientId"),
+        redirect_uri: attribute("redirectUri"),
+        parameters: oauth
+            .children()
+            .filter(|n| n.has_tag_name("parameter"))
+            .filter_map(|p| {
+                Some((
+                    p.attribute("key")?.to_string(),
+                    p.attribute("value")?.to_string(),
+                ))
+            })
+            .collect(),
+    })
+}
+
+/// Parses `providers.xml`.
+///
+/// The `domain` attribute holds comma-separated regular expressions whose
+/// backslashes are escaped once more for Android resources.
+pub fn parse(src: &str) -> Result<Vec<Entry>> {
+    let doc = Document::parse(src)?;
+    let mut entries = Vec::new();
+    for provider in doc
+        .root_element()
+        .children()
+        .filter(|n| n.has_tag_name("provider"))
+    {
+        let name = provider.attribute("name").unwrap_or_default();
+        let regular = provider
+            .attribute("domain")
+            .map(|d| {
+                d.replace("\\\\", "\\")
+                    .split(',')
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let oauth = provider
+            .children()
+            .any(|n| n.has_tag_name("oauth") && n.attribute("enabled") != Some("false"));
+        let auth: &[Authentication] = if oauth {
+            &[Authentication::OAuth2]
+        } else {
+            &[]
+        };
+
+        let mut entry = Entry::new(Source::FairEmail, name);
+        entry.domain_regular = regular;
+        entry.incoming_servers = setservers(provider, "imap", Protocol::Imap, auth);
+        entry
+            .incoming_servers
+            .extend(setservers(provider, "pop", Protocol::Pop3, auth));
+        entry.outgoing_servers = setservers(provider, "smtp", Protocol::Smtp, auth);
+        entry.oauth2 = provider
+            .children()
+            .filter(|n| n.has_tag_name("oauth"))
+            .find_map(oauth2);
+
+        if super::is_domain_name(name) {
+            entry.domain = name.to_lowercase();
+        } else {
+            entry.provider = Some(name.to_string());
+            let first = entry.servers().next();
+            if let Some(domain) = first.map(|s| super::registered_domain(&s.hostname)) {
+                entry.domain = domain;
+            }
+        }
+        entries.push(entry);
+    }
+    Ok(entries)
+}
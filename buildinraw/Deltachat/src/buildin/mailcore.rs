@@ -0,0 +1,92 @@
+//! MailCore2 `providers.json`.
+
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+use super::Source;
+use crate::provider::{Direction, Protocol, Socket};
+use crate::schema::{Entry, MailServer};
+
+#[derive(Debug, Deserialize)]
+struct Provider {
+    #[serde(default)]
+    servers: Map<String, Value>,
+    #[serde(default, rename = "domain-match")]
+    domain_match: Vec<String>,
+    #[serde(default, rename = "mx-match")]
+    mx_match: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Server {
+    hostname: Option<String>,
+    port: u16,
+    #[serde(default)]
+    ssl: bool,
+    #[serde(default)]
+    tls: bool,
+    #[serde(default)]
+    starttls: bool,
+}
+
+/// Parses `providers.json`.
+///
+/// Servers without a hostname are templates MailCore2 fills in from the
+/// user's domain and are skipped, as are providers left without incoming or
+/// outgoing servers.
+pub fn parse(src: &str) -> Result<Vec<Entry>> {
+    let providers: Map<String, Value> = serde_json::from_str(src)?;
+    let mut entries = Vec::new();
+    for (name, provider) in providers {
+        let provider: Provider = serde_json::from_value(provider)?;
+        let mut entry = Entry::new(Source::Mailcore, name.clone());
+        for (proto, servers) in provider.servers {
+            let protocol = match proto.as_str() {
+                "imap" => Protocol::Imap,
+                "pop" => Protocol::Pop3,
+                "smtp" => Protocol::Smtp,
+                _ => continue,
+            };
+            let servers: Vec<Server> = serde_json::from_value(servers)?;
+            for server in servers {
+                let Some(hostname) = server.hostname else {
+                    continue;
+                };
+                let socket = if server.ssl || server.tls {
+                    Socket::Ssl
+                } else if server.starttls {
+                    Socket::Starttls
+                } else {
+                    Socket::Plain
+                };
+                let server = MailServer {
+                    protocol,
+                    hostname,
+                    port: server.port,
+                    socket_type: socket,
+                    authentication: Vec::new(),
+                    username: None,
+                };
+                match protocol.direction() {
+                    Direction::Incoming => entry.incoming_servers.push(server),
+                    Direction::Outgoing => entry.outgoing_servers.push(server),
+                }
+            }
+        }
+        if entry.incoming_servers.is_empty() || entry.outgoing_servers.is_empty() {
+            continue;
+        }
+
+        if super::is_domain_name(&name) {
+            entry.domain = name.to_lowercase();
+        } else if provider.domain_match.is_empty() && provider.mx_match.is_empty() {
+            entry.domain = super::registered_domain(&entry.incoming_servers[0].hostname);
+            entry.provider = Some(name);
+        }
+        entry.domain_regular = provider.domain_match;
+        entry.mx_regular = provider.mx_match;
+        entries.push(entry);
+    }
+    Ok(entries)
+}
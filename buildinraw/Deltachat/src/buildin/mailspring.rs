@@ -0,0 +1,76 @@
+//! Mailspring `mailspring-provider-settings.json`.
+
+use anyhow::{Context as _, Result};
+use serde_json::{Map, Value};
+
+use super::Source;
+use crate::provider::{Protocol, Socket};
+use crate::schema::{Authentication, Entry, MailServer};
+
+/// Reads the `<proto>_host`, `<proto>_port`, `<proto>_security` and
+/// `<proto>_authentication` settings of one protocol.
+///
+/// Some entries leave the server empty; those yield `None`.
+fn setserver(
+    protocol: Protocol,
+    proto: &str,
+    var: &Map<String, Value>,
+) -> Result<Option<MailServer>> {
+    let field = |name: &str| var.get(&format!("{proto}_{name}"));
+    let hostname = field("host")
+        .and_then(Value::as_str)
+        .with_context(|| format!("missing {proto}_host"))?;
+    let Some(port) = field("port").and_then(super::port) else {
+        return Ok(None);
+    };
+    let socket = match field("security").and_then(Value::as_str) {
+        Some("SSL / TLS") => Socket::Ssl,
+        Some("STARTTLS") => Socket::Starttls,
+        _ => Socket::Plain,
+    };
+    let authentication = match field("authentication") {
+        Some(Value::Array(methods)) => methods
+            .iter()
+            .filter_map(Value::as_str)
+            .filter_map(Authentication::from_mailspring)
+            .collect(),
+        Some(Value::String(method)) => Authentication::from_mailspring(method)
+            .into_iter()
+            .collect(),
+        _ => Vec::new(),
+    };
+    Ok(Some(MailServer {
+        protocol,
+        hostname: hostname.to_string(),
+        port,
+        socket_type: socket,
+        authentication,
+        username: None,
+    }))
+}
+
+/// Parses the settings, resolving `alias` entries to the domain they point to.
+pub fn parse(src: &str) -> Result<Vec<Entry>> {
+    let settings: Map<String, Value> = serde_json::from_str(src)?;
+    let mut entries = Vec::new();
+    for (domain, val) in &settings {
+        let mut val = val;
+        if let Some(alias) = val.get("alias").and_then(Value::as_str) {
+            val = settings
+                .get(alias)
+                .with_context(|| format!("{domain}: unknown alias {alias:?}"))?;
+        }
+        let var = val
+            .as_object()
+            .with_context(|| format!("{domain}: settings are not an object"))?;
+        let mut entry = Entry::new(Source::Mailspring, domain.clone());
+        entry
+            .incoming_servers
+            .extend(setserver(Protocol::Imap, "imap", var)?);
+        entry
+            .outgoing_servers
+            .extend(setserver(Protocol::Smtp, "smtp", var)?);
+        entries.push(entry);
+    }
+    Ok(entries)
+}
@@ -0,0 +1,65 @@
+//! Nodemailer `services.json`.
+//!
+//! Nodemailer only sends mail, so every entry has a single SMTP server.
+
+use anyhow::Result;
+use serde::Deserialize;
+
+use super::Source;
+use crate::provider::{Protocol, Socket};
+use crate::schema::{Authentication, Entry, MailServer};
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Service {
+    #[serde(default)]
+    domains: Vec<String>,
+    #[serde(default)]
+    aliases: Vec<String>,
+    host: Option<String>,
+    port: Option<serde_json::Value>,
+    secure: Option<bool>,
+    auth_method: Option<String>,
+}
+
+/// Parses `services.json`, emitting one entry for the service name and each
+/// of its domains and aliases.
+pub fn parse(src: &str) -> Result<Vec<Entry>> {
+    let services: serde_json::Map<String, serde_json::Value> = serde_json::from_str(src)?;
+    let mut entries = Vec::new();
+    for (name, service) in services {
+        let service: Service = serde_json::from_value(service)?;
+        let (Some(host), Some(port)) = (service.host, service.port.as_ref().and_then(super::port))
+        else {
+            continue;
+        };
+        let socket = match service.secure {
+            Some(true) => Socket::Ssl,
+            Some(false) if port == 587 => Socket::Starttls,
+            Some(false) => Socket::Plain,
+            None => super::socket_for_port(port),
+        };
+        let server = MailServer {
+            protocol: Protocol::Smtp,
+            hostname: host,
+            port,
+            socket_type: socket,
+            authentication: service
+                .auth_method
+                .as_deref()
+                .and_then(Authentication::from_nodemailer)
+                .into_iter()
+                .collect(),
+            username: None,
+        };
+        for domain in std::iter::once(name)
+            .chain(service.domains)
+            .chain(service.aliases)
+        {
+            let mut entry = Entry::new(Source::Nodemailer, domain);
+            entry.outgoing_servers.push(server.clone());
+            entries.push(entry);
+        }
+    }
+    Ok(entries)
+}
@@ -0,0 +1,658 @@
+//! Delta Chat configuration keys.
+//!
+//! Values are stored as strings, as in upstream's `config` table; the types
+//! below say how each key's string is to be read.
+
+use std::fmt;
+use std::time::Duration;
+
+use anyhow::{bail, Context as _, Result};
+use serde::{Serialize, Serializer};
+use strum::EnumProperty as _;
+use strum_macros::{AsRefStr, Display, EnumIter, EnumProperty, EnumString};
+
+/// The available configuration keys.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Display,
+    EnumString,
+    AsRefStr,
+    EnumIter,
+    EnumProperty,
+    PartialOrd,
+    Ord,
+)]
+#[strum(serialize_all = "snake_case")]
+pub enum Config {
+    /// Email address, used in the `From:` field.
+    Addr,
+
+    /// IMAP server hostname.
+    MailServer,
+
+    /// IMAP server username.
+    MailUser,
+
+    /// IMAP server password.
+    MailPw,
+
+    /// IMAP server port.
+    MailPort,
+
+    /// IMAP server security (e.g. TLS, STARTTLS).
+    MailSecurity,
+
+    /// How to check IMAP server TLS certificates.
+    ImapCertificateChecks,
+
+    /// SMTP server hostname.
+    SendServer,
+
+    /// SMTP server username.
+    SendUser,
+
+    /// SMTP server password.
+    SendPw,
+
+    /// SMTP server port.
+    SendPort,
+
+    /// SMTP server security (e.g. TLS, STARTTLS).
+    SendSecurity,
+
+    /// How to check SMTP server TLS certificates.
+    SmtpCertificateChecks,
+
+    /// Whether to use OAuth 2.
+    ///
+    /// Historically contained other bitflags, which are now deprecated.
+    /// Should not be extended in the future, create new config keys instead.
+    ServerFlags,
+
+    /// True if SOCKS5 is enabled.
+    ///
+    /// Can be used to disable SOCKS5 without erasing SOCKS5 configuration.
+    Socks5Enabled,
+
+    /// SOCKS5 proxy server hostname or address.
+    Socks5Host,
+
+    /// SOCKS5 proxy server port.
+    Socks5Port,
+
+    /// SOCKS5 proxy server username.
+    Socks5User,
+
+    /// SOCKS5 proxy server password.
+    Socks5Password,
+
+    /// Own name to use in the `From:` field when sending messages.
+    Displayname,
+
+    /// Own status to display, sent in message footer.
+    Selfstatus,
+
+    /// Own avatar filename.
+    Selfavatar,
+
+    /// Send BCC copy to self.
+    ///
+    /// Should be enabled for multidevice setups.
+    #[strum(props(default = "1"))]
+    BccSelf,
+
+    /// True if encryption is preferred according to Autocrypt standard.
+    #[strum(props(default = "1"))]
+    E2eeEnabled,
+
+    /// True if Message Delivery Notifications (read receipts) should
+    /// be sent and requested.
+    #[strum(props(default = "1"))]
+    MdnsEnabled,
+
+    /// True if "Sent" folder should be watched for changes.
+    #[strum(props(default = "0"))]
+    SentboxWatch,
+
+    /// True if chat messages should be moved to a separate folder.
+    #[strum(props(default = "1"))]
+    MvboxMove,
+
+    /// Watch for new messages in the "Mvbox" (aka DeltaChat folder) only.
+    ///
+    /// This will not entirely disable other folders, e.g. the spam folder will also still
+    /// be watched for new messages.
+    #[strum(props(default = "0"))]
+    OnlyFetchMvbox,
+
+    /// Whether to show classic emails or only chat messages.
+    #[strum(props(default = "2"))] // also change ShowEmails.default() on changes
+    ShowEmails,
+
+    /// Quality of the media files to send.
+    #[strum(props(default = "0"))] // also change MediaQuality.default() on changes
+    MediaQuality,
+
+    /// If set to "1", on the first time `start_io()` is called after configuring,
+    /// the newest existing messages are fetched.
+    /// Existing recipients are added to the contact database regardless of this setting.
+    #[strum(props(default = "0"))]
+    FetchExistingMsgs,
+
+    /// If set to "1", then existing messages are considered to be already fetched.
+    /// This flag is reset after successful configuration.
+    #[strum(props(default = "1"))]
+    FetchedExistingMsgs,
+
+    /// Type of the OpenPGP key to generate.
+    #[strum(props(default = "0"))]
+    KeyGenType,
+
+    /// Timer in seconds after which the message is deleted from the
+    /// server.
+    ///
+    /// Equals to 0 by default, which means the message is never
+    /// deleted.
+    ///
+    /// Value 1 is treated as "delete at once": messages are deleted
+    /// immediately, without moving to DeltaChat folder.
+    #[strum(props(default = "0"))]
+    DeleteServerAfter,
+
+    /// Timer in seconds after which the message is deleted from the
+    /// device.
+    ///
+    /// Equals to 0 by default, which means the message is never
+    /// deleted.
+    #[strum(props(default = "0"))]
+    DeleteDeviceAfter,
+
+    /// Move messages to the Trash folder instead of marking them "\Deleted". Overrides
+    /// `ProviderOptions::delete_to_trash`.
+    DeleteToTrash,
+
+    /// Save raw MIME messages with headers in the database if true.
+    SaveMimeHeaders,
+
+    /// The primary email address. Also see `SecondaryAddrs`.
+    ConfiguredAddr,
+
+    /// Configured IMAP server hostname.
+    ConfiguredMailServer,
+
+    /// Configured IMAP server username.
+    ConfiguredMailUser,
+
+    /// Configured IMAP server password.
+    ConfiguredMailPw,
+
+    /// Configured IMAP server port.
+    ConfiguredMailPort,
+
+    /// Configured IMAP server security (e.g. TLS, STARTTLS).
+    ConfiguredMailSecurity,
+
+    /// How to check IMAP server TLS certificates.
+    ConfiguredImapCertificateChecks,
+
+    /// Configured SMTP server hostname.
+    ConfiguredSendServer,
+
+    /// Configured SMTP server username.
+    ConfiguredSendUser,
+
+    /// Configured SMTP server password.
+    ConfiguredSendPw,
+
+    /// Configured SMTP server port.
+    ConfiguredSendPort,
+
+    /// How to check SMTP server TLS certificates.
+    ConfiguredSmtpCertificateChecks,
+
+    /// Whether OAuth 2 is used with configured provider.
+    ConfiguredServerFlags,
+
+    /// Configured SMTP server security (e.g. TLS, STARTTLS).
+    ConfiguredSendSecurity,
+
+    /// Configured folder for incoming messages.
+    ConfiguredInboxFolder,
+
+    /// Configured folder for chat messages.
+    ConfiguredMvboxFolder,
+
+    /// Configured "Sent" folder.
+    ConfiguredSentboxFolder,
+
+    /// Configured "Trash" folder.
+    ConfiguredTrashFolder,
+
+    /// Unix timestamp of the last successful configuration.
+    ConfiguredTimestamp,
+
+    /// ID of the configured provider from the provider database.
+    ConfiguredProvider,
+
+    /// True if account is configured.
+    Configured,
+
+    /// All secondary self addresses separated by spaces
+    /// (`addr1@example.org addr2@example.org addr3@example.org`)
+    SecondaryAddrs,
+
+    /// Read-only core version string.
+    #[strum(serialize = "sys.version")]
+    SysVersion,
+
+    /// Maximal recommended attachment size in bytes.
+    #[strum(serialize = "sys.msgsize_max_recommended")]
+    SysMsgsizeMaxRecommended,
+
+    /// Space separated list of all config keys available.
+    #[strum(serialize = "sys.config_keys")]
+    SysConfigKeys,
+
+    /// True if it is a bot account.
+    Bot,
+
+    /// True when to skip initial start messages in groups.
+    #[strum(props(default = "0"))]
+    SkipStartMessages,
+
+    /// Whether we send a warning if the password is wrong (set to false when we send a warning
+    /// because we do not want to send a second warning)
+    #[strum(props(default = "0"))]
+    NotifyAboutWrongPw,
+
+    /// If a warning about exceeding quota was shown recently,
+    /// this is the percentage of quota at the time the warning was given.
+    /// Unset, when quota falls below minimal warning threshold again.
+    QuotaExceeding,
+
+    /// address to webrtc instance to use for videochats
+    WebrtcInstance,
+
+    /// Timestamp of the last time housekeeping was run
+    LastHousekeeping,
+
+    /// Timestamp of the last `CantDecryptOutgoingMsgs` notification.
+    LastCantDecryptOutgoingMsgs,
+
+    /// To how many seconds to debounce scan_all_folders. Used mainly in tests, to disable debouncing completely.
+    #[strum(props(default = "60"))]
+    ScanAllFoldersDebounceSecs,
+
+    /// Whether to avoid using IMAP IDLE even if the server supports it.
+    ///
+    /// This is a developer option for testing "fake idle".
+    #[strum(props(default = "0"))]
+    DisableIdle,
+
+    /// Defines the max. size (in bytes) of messages downloaded automatically.
+    /// 0 = no limit.
+    #[strum(props(default = "0"))]
+    DownloadLimit,
+
+    /// Enable sending and executing (applying) sync messages. Sending requires `BccSelf` to be set.
+    #[strum(props(default = "1"))]
+    SyncMsgs,
+
+    /// Space-separated list of all the authserv-ids which we believe
+    /// may be the one of our email server.
+    ///
+    /// See `crate::authres::update_authservid_candidates`.
+    AuthservIdCandidates,
+
+    /// Make all outgoing messages with Autocrypt header "multipart/signed".
+    SignUnencrypted,
+
+    /// Let the core save all events to the database.
+    /// This value is used internally to remember the MsgId of the logging xdc
+    #[strum(props(default = "0"))]
+    DebugLogging,
+
+    /// Last message processed by the bot.
+    LastMsgId,
+
+    /// How often to gossip Autocrypt keys in chats with multiple recipients, in seconds. 2 days by
+    /// default.
+    ///
+    /// This is not supposed to be changed by UIs and only used for testing.
+    #[strum(props(default = "172800"))]
+    GossipPeriod,
+
+    /// Feature flag for verified 1:1 chats; the UI should set it
+    /// to 1 if it supports verified 1:1 chats.
+    /// Regardless of this setting, `chat.is_protected()` returns true while the key is verified,
+    /// and when the key changes, an info message is posted into the chat.
+    /// 0=Nothing else happens when the key changes.
+    /// 1=After the key changed, `can_send()` returns false and `is_protection_broken()` returns true
+    /// until `chat_id.accept()` is called.
+    #[strum(props(default = "0"))]
+    VerifiedOneOnOneChats,
+
+    /// Row ID of the key in the `keypairs` table
+    /// used for signatures, encryption to self and included in `Autocrypt` header.
+    KeyId,
+
+    /// This key is sent to the self_reporting bot so that the bot can recognize the user
+    /// without storing the email address
+    SelfReportingId,
+}
+
+/// Whether to show classic emails or only chat messages, upstream
+/// `constants::ShowEmails`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShowEmails {
+    Off = 0,
+    AcceptedContacts = 1,
+    #[default] // also change Config.ShowEmails props(default) on changes
+    All = 2,
+}
+
+/// Quality of the media files to send, upstream `constants::MediaQuality`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MediaQuality {
+    #[default] // also change Config.MediaQuality props(default) on changes
+    Balanced = 0,
+    Worse = 1,
+}
+
+/// How the value of a configuration key is read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum ConfigType {
+    /// `0` or `1`.
+    Bool,
+
+    /// Signed integer, e.g. a port, a size in bytes or a timestamp.
+    Int,
+
+    /// Non-negative number of seconds.
+    Seconds,
+
+    /// [`ShowEmails`] as its number.
+    ShowEmails,
+
+    /// [`MediaQuality`] as its number.
+    MediaQuality,
+
+    /// Any string.
+    Text,
+}
+
+/// Typed value of a configuration key.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigValue {
+    Bool(bool),
+    Int(i64),
+    Seconds(u64),
+    ShowEmails(ShowEmails),
+    MediaQuality(MediaQuality),
+    Text(String),
+}
+
+impl ConfigValue {
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ConfigValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            ConfigValue::Int(i) => Some(*i),
+            _ => None,
+        }
+    }
+
+    pub fn as_duration(&self) -> Option<Duration> {
+        match self {
+            ConfigValue::Seconds(secs) => Some(Duration::from_secs(*secs)),
+            _ => None,
+        }
+    }
+
+    pub fn as_show_emails(&self) -> Option<ShowEmails> {
+        match self {
+            ConfigValue::ShowEmails(v) => Some(*v),
+            _ => None,
+        }
+    }
+
+    pub fn as_media_quality(&self) -> Option<MediaQuality> {
+        match self {
+            ConfigValue::MediaQuality(v) => Some(*v),
+            _ => None,
+        }
+    }
+}
+
+/// Formats the value the way it is stored.
+impl fmt::Display for ConfigValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigValue::Bool(b) => write!(f, "{}", u8::from(*b)),
+            ConfigValue::Int(i) => write!(f, "{i}"),
+            ConfigValue::Seconds(secs) => write!(f, "{secs}"),
+            ConfigValue::ShowEmails(v) => write!(f, "{}", *v as u8),
+            ConfigValue::MediaQuality(v) => write!(f, "{}", *v as u8),
+            ConfigValue::Text(s) => f.write_str(s),
+        }
+    }
+}
+
+/// Serialized in the stored form too, so JSON output matches the database.
+impl Serialize for ConfigValue {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Serialized as the key name, `bcc_self` rather than `BccSelf`.
+impl Serialize for Config {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_ref())
+    }
+}
+
+impl Config {
+    /// How values of this key are read.
+    pub fn value_type(self) -> ConfigType {
+        use Config::*;
+        match self {
+            Socks5Enabled
+            | BccSelf
+            | E2eeEnabled
+            | MdnsEnabled
+            | SentboxWatch
+            | MvboxMove
+            | OnlyFetchMvbox
+            | FetchExistingMsgs
+            | FetchedExistingMsgs
+            | DeleteToTrash
+            | SaveMimeHeaders
+            | Configured
+            | Bot
+            | SkipStartMessages
+            | NotifyAboutWrongPw
+            | DisableIdle
+            | SyncMsgs
+            | SignUnencrypted
+            | VerifiedOneOnOneChats => ConfigType::Bool,
+
+            DeleteServerAfter | DeleteDeviceAfter | ScanAllFoldersDebounceSecs | GossipPeriod => {
+                ConfigType::Seconds
+            }
+
+            MailPort
+            | MailSecurity
+            | ImapCertificateChecks
+            | SendPort
+            | SendSecurity
+            | SmtpCertificateChecks
+            | ServerFlags
+            | Socks5Port
+            | KeyGenType
+            | ConfiguredMailPort
+            | ConfiguredMailSecurity
+            | ConfiguredImapCertificateChecks
+            | ConfiguredSendPort
+            | ConfiguredSmtpCertificateChecks
+            | ConfiguredServerFlags
+            | ConfiguredSendSecurity
+            | ConfiguredTimestamp
+            | SysMsgsizeMaxRecommended
+            | QuotaExceeding
+            | LastHousekeeping
+            | LastCantDecryptOutgoingMsgs
+            | DownloadLimit
+            | DebugLogging
+            | LastMsgId
+            | KeyId => ConfigType::Int,
+
+            ShowEmails => ConfigType::ShowEmails,
+            MediaQuality => ConfigType::MediaQuality,
+
+            Addr
+            | MailServer
+            | MailUser
+            | MailPw
+            | SendServer
+            | SendUser
+            | SendPw
+            | Socks5Host
+            | Socks5User
+            | Socks5Password
+            | Displayname
+            | Selfstatus
+            | Selfavatar
+            | ConfiguredAddr
+            | ConfiguredMailServer
+            | ConfiguredMailUser
+            | ConfiguredMailPw
+            | ConfiguredSendServer
+            | ConfiguredSendUser
+            | ConfiguredSendPw
+            | ConfiguredInboxFolder
+            | ConfiguredMvboxFolder
+            | ConfiguredSentboxFolder
+            | ConfiguredTrashFolder
+            | ConfiguredProvider
+            | SecondaryAddrs
+            | SysVersion
+            | SysConfigKeys
+            | WebrtcInstance
+            | AuthservIdCandidates
+            | SelfReportingId => ConfigType::Text,
+        }
+    }
+
+    /// Reads a stored value of this key.
+    pub fn parse_value(self, value: &str) -> Result<ConfigValue> {
+        let int = || -> Result<i64> {
+            value
+                .parse()
+                .with_context(|| format!("{self}: expected an integer, got {value:?}"))
+        };
+        Ok(match self.value_type() {
+            ConfigType::Bool => match value {
+                "0" => ConfigValue::Bool(false),
+                "1" => ConfigValue::Bool(true),
+                _ => bail!("{self}: expected 0 or 1, got {value:?}"),
+            },
+            ConfigType::Int => ConfigValue::Int(int()?),
+            ConfigType::Seconds => {
+                ConfigValue::Seconds(value.parse().with_context(|| {
+                    format!("{self}: expected a number of seconds, got {value:?}")
+                })?)
+            }
+            ConfigType::ShowEmails => ConfigValue::ShowEmails(match int()? {
+                0 => ShowEmails::Off,
+                1 => ShowEmails::AcceptedContacts,
+                2 => ShowEmails::All,
+                _ => bail!("{self}: expected 0, 1 or 2, got {value:?}"),
+            }),
+            ConfigType::MediaQuality => ConfigValue::MediaQuality(match int()? {
+                0 => MediaQuality::Balanced,
+                1 => MediaQuality::Worse,
+                _ => bail!("{self}: expected 0 or 1, got {value:?}"),
+            }),
+            ConfigType::Text => ConfigValue::Text(value.to_string()),
+        })
+    }
+
+    /// Built-in default of this key, from its `default` property.
+    pub fn default_value(self) -> Option<ConfigValue> {
+        let value = self.get_str("default")?;
+        Some(
+            self.parse_value(value)
+                .expect("built-in defaults match their type"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(
+            Config::BccSelf.parse_value("1").unwrap().as_bool(),
+            Some(true)
+        );
+        assert_eq!(
+            Config::DeleteServerAfter
+                .parse_value("3600")
+                .unwrap()
+                .as_duration(),
+            Some(Duration::from_secs(3600))
+        );
+        assert_eq!(
+            Config::ShowEmails
+                .parse_value("1")
+                .unwrap()
+                .as_show_emails(),
+            Some(ShowEmails::AcceptedContacts)
+        );
+        assert_eq!(
+            Config::Addr
+                .parse_value("a@example.org")
+                .unwrap()
+                .to_string(),
+            "a@example.org"
+        );
+        assert!(Config::BccSelf.parse_value("yes").is_err());
+        assert!(Config::DeleteServerAfter.parse_value("-1").is_err());
+        assert!(Config::MediaQuality.parse_value("2").is_err());
+    }
+
+    #[test]
+    fn test_default_values() {
+        for key in Config::iter() {
+            // Panics if a default does not match the key's type.
+            if let Some(value) = key.default_value() {
+                assert_eq!(Some(value.to_string().as_str()), key.get_str("default"));
+            }
+        }
+        assert_eq!(
+            Config::ShowEmails.default_value().unwrap().as_show_emails(),
+            Some(ShowEmails::default())
+        );
+        assert_eq!(
+            Config::MediaQuality
+                .default_value()
+                .unwrap()
+                .as_media_quality(),
+            Some(MediaQuality::default())
+        );
+        assert_eq!(Config::Addr.default_value(), None);
+    }
+}
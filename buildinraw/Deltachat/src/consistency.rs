@@ -0,0 +1,490 @@
+//! Cross-list inconsistency report.
+//!
+//! Entries of all built-in lists are grouped by domain, and for every domain
+//! the lists carrying it are compared protocol by protocol. A list is only
+//! compared on protocols it carries at all, so Nodemailer, which only lists
+//! SMTP servers, is never reported as missing IMAP.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+use serde::Serialize;
+
+use crate::buildin::Source;
+use crate::provider::{Protocol, Socket};
+use crate::psl;
+use crate::schema::{self, Entry, MailServer};
+
+/// Protocols compared, in report order.
+const PROTOCOLS: [Protocol; 6] = [
+    Protocol::Imap,
+    Protocol::Pop3,
+    Protocol::Jmap,
+    Protocol::Ews,
+    Protocol::ActiveSync,
+    Protocol::Smtp,
+];
+
+/// Inconsistency found for one domain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Finding {
+    /// Lists name different hosts for a protocol.
+    Hostname {
+        protocol: Protocol,
+        values: BTreeMap<String, Vec<Source>>,
+    },
+
+    /// Lists name different ports for a protocol.
+    Port {
+        protocol: Protocol,
+        values: BTreeMap<u16, Vec<Source>>,
+    },
+
+    /// Lists name different socket types for a protocol.
+    Socket {
+        protocol: Protocol,
+        values: BTreeMap<String, Vec<Source>>,
+    },
+
+    /// Some lists have servers for a protocol, others that carry the
+    /// protocol for other domains do not.
+    MissingProtocol {
+        protocol: Protocol,
+        present: Vec<Source>,
+        missing: Vec<Source>,
+    },
+
+    /// A list only offers plaintext connections for a protocol.
+    PlaintextOnly { protocol: Protocol, source: Source },
+}
+
+impl Finding {
+    /// Short name used in the summary table.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Finding::Hostname { .. } => "hostname",
+            Finding::Port { .. } => "port",
+            Finding::Socket { .. } => "socket",
+            Finding::MissingProtocol { .. } => "missing_protocol",
+            Finding::PlaintextOnly { .. } => "plaintext_only",
+        }
+    }
+
+    fn details(&self) -> String {
+        fn values<K: std::fmt::Display>(values: &BTreeMap<K, Vec<Source>>) -> String {
+            values
+                .iter()
+                .map(|(value, sources)| format!("{value} ({})", keys(sources)))
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+        match self {
+            Finding::Hostname {
+                protocol,
+                values: v,
+            } => format!("{protocol:?}: {}", values(v)),
+            Finding::Port {
+                protocol,
+                values: v,
+            } => format!("{protocol:?}: {}", values(v)),
+            Finding::Socket {
+                protocol,
+                values: v,
+            } => format!("{protocol:?}: {}", values(v)),
+            Finding::MissingProtocol {
+                protocol,
+                present,
+                missing,
+            } => format!(
+                "{protocol:?} in {}, not in {}",
+                keys(present),
+                keys(missing)
+            ),
+            Finding::PlaintextOnly { protocol, source } => {
+                format!("{protocol:?} in {}", source.key())
+            }
+        }
+    }
+}
+
+/// Registered domain of `domain`, or `domain` itself if it has none, e.g.
+/// for wildcard patterns such as `*.hermes.radio`.
+fn organization(domain: &str) -> String {
+    psl::registered_domain(domain.trim_start_matches("*.")).unwrap_or_else(|| domain.to_string())
+}
+
+fn keys(sources: &[Source]) -> String {
+    sources
+        .iter()
+        .map(|s| s.key())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Findings for one domain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DomainReport {
+    /// Domain, lowercase.
+    pub domain: String,
+
+    /// Registered domain of the domain by the Public Suffix List, grouping
+    /// domains of one organization such as `zoho.com` and `mail.zoho.com`.
+    pub organization: String,
+
+    /// Lists carrying the domain.
+    pub sources: Vec<Source>,
+
+    /// Inconsistencies, never empty.
+    pub findings: Vec<Finding>,
+}
+
+/// Inconsistencies across all lists, by domain.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Report {
+    /// Domains with findings, sorted by domain.
+    pub domains: Vec<DomainReport>,
+}
+
+impl Report {
+    /// Compares the given lists.
+    pub fn new(lists: &[(Source, Vec<Entry>)]) -> Self {
+        // Protocols each list carries at all.
+        let carried: BTreeMap<Source, BTreeSet<Protocol>> = lists
+            .iter()
+            .map(|(source, entries)| {
+                let protocols = entries
+                    .iter()
+                    .flat_map(Entry::servers)
+                    .map(|s| s.protocol)
+                    .collect();
+                (*source, protocols)
+            })
+            .collect();
+
+        // Servers by domain and list; a list may have several entries per domain.
+        let mut domains: BTreeMap<String, BTreeMap<Source, Vec<&MailServer>>> = BTreeMap::new();
+        for (source, entries) in lists {
+            for entry in entries {
+                domains
+                    .entry(entry.domain.to_lowercase())
+                    .or_default()
+                    .entry(*source)
+                    .or_default()
+                    .extend(entry.servers());
+            }
+        }
+
+        let domains = domains
+            .into_iter()
+            .filter_map(|(domain, servers)| {
+                let findings = compare(&servers, &carried);
+                (!findings.is_empty()).then(|| DomainReport {
+                    organization: organization(&domain),
+                    domain,
+                    sources: servers.keys().copied().collect(),
+                    findings,
+                })
+            })
+            .collect();
+        Self { domains }
+    }
+
+    /// Reads all lists of a `buildinlists` directory and compares them.
+    pub fn from_lists_dir(dir: &Path) -> Result<Self> {
+        let lists = Source::ALL
+            .into_iter()
+            .map(|source| {
+                let path = dir.join(source.list_file());
+                let src = std::fs::read_to_string(&path)
+                    .with_context(|| format!("cannot read {}", path.display()))?;
+                let entries = schema::read_list(source, &src)
+                    .with_context(|| format!("cannot parse {}", path.display()))?;
+                Ok((source, entries))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(Self::new(&lists))
+    }
+
+    /// Domains with findings grouped by organization.
+    pub fn by_organization(&self) -> BTreeMap<&str, Vec<&DomainReport>> {
+        let mut groups: BTreeMap<&str, Vec<&DomainReport>> = BTreeMap::new();
+        for domain in &self.domains {
+            groups
+                .entry(domain.organization.as_str())
+                .or_default()
+                .push(domain);
+        }
+        groups
+    }
+
+    /// Number of findings of each kind.
+    pub fn counts(&self) -> BTreeMap<&'static str, usize> {
+        let mut counts = BTreeMap::new();
+        for finding in self.domains.iter().flat_map(|d| &d.findings) {
+            *counts.entry(finding.kind()).or_default() += 1;
+        }
+        counts
+    }
+
+    /// Writes a human-readable table, one finding per row, followed by the
+    /// number of findings of each kind.
+    pub fn write_table(&self, out: &mut dyn Write) -> Result<()> {
+        let width = self
+            .domains
+            .iter()
+            .map(|d| d.domain.len())
+            .max()
+            .unwrap_or_default()
+            .max("DOMAIN".len());
+        writeln!(out, "{:width$}  {:16}  DETAILS", "DOMAIN", "FINDING")?;
+        for domain in &self.domains {
+            for finding in &domain.findings {
+                writeln!(
+                    out,
+                    "{:width$}  {:16}  {}",
+                    domain.domain,
+                    finding.kind(),
+                    finding.details()
+                )?;
+            }
+        }
+        writeln!(out)?;
+        writeln!(out, "{} domains with findings", self.domains.len())?;
+        for (kind, count) in self.counts() {
+            writeln!(out, "{kind:16}  {count}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Groups a value of each list's servers for one protocol, reporting it if
+/// the lists do not all agree.
+fn disagreement<K: Ord>(
+    servers: &BTreeMap<Source, Vec<&MailServer>>,
+    protocol: Protocol,
+    value: impl Fn(&MailServer) -> K,
+) -> Option<BTreeMap<K, Vec<Source>>> {
+    let mut sets: Vec<BTreeSet<K>> = Vec::new();
+    let mut values: BTreeMap<K, Vec<Source>> = BTreeMap::new();
+    for (source, servers) in servers {
+        let set: BTreeSet<K> = servers
+            .iter()
+            .filter(|s| s.protocol == protocol)
+            .map(|s| value(s))
+            .collect();
+        if set.is_empty() {
+            continue;
+        }
+        for server in servers.iter().filter(|s| s.protocol == protocol) {
+            let sources = values.entry(value(server)).or_default();
+            if !sources.contains(source) {
+                sources.push(*source);
+            }
+        }
+        sets.push(set);
+    }
+    let agree = sets.windows(2).all(|w| w[0] == w[1]);
+    (!agree).then_some(values)
+}
+
+fn compare(
+    servers: &BTreeMap<Source, Vec<&MailServer>>,
+    carried: &BTreeMap<Source, BTreeSet<Protocol>>,
+) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for protocol in PROTOCOLS {
+        let has = |source: &Source| servers[source].iter().any(|s| s.protocol == protocol);
+        let present: Vec<Source> = servers.keys().copied().filter(has).collect();
+        if present.is_empty() {
+            continue;
+        }
+        let missing: Vec<Source> = servers
+            .keys()
+            .copied()
+            .filter(|s| !has(s) && carried.get(s).is_some_and(|p| p.contains(&protocol)))
+            .collect();
+        if !missing.is_empty() {
+            findings.push(Finding::MissingProtocol {
+                protocol,
+                present: present.clone(),
+                missing,
+            });
+        }
+
+        if let Some(values) = disagreement(servers, protocol, |s| s.hostname.to_lowercase()) {
+            findings.push(Finding::Hostname { protocol, values });
+        }
+        if let Some(values) = disagreement(servers, protocol, |s| s.port) {
+            findings.push(Finding::Port { protocol, values });
+        }
+        if let Some(values) = disagreement(servers, protocol, |s| format!("{:?}", s.socket_type)) {
+            findings.push(Finding::Socket { protocol, values });
+        }
+
+        for source in present {
+            let plaintext_only = servers[&source]
+                .iter()
+                .filter(|s| s.protocol == protocol)
+                .all(|s| s.socket_type == Socket::Plain);
+            if plaintext_only {
+                findings.push(Finding::PlaintextOnly { protocol, source });
+            }
+        }
+    }
+    findings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::Direction;
+
+    fn entry(source: Source, domain: &str, servers: &[(Protocol, &str, u16, Socket)]) -> Entry {
+        let mut entry = Entry::new(source, domain);
+        for &(protocol, hostname, port, socket_type) in servers {
+            let server = MailServer {
+                protocol,
+                hostname: hostname.to_string(),
+                port,
+                socket_type,
+                authentication: Vec::new(),
+                username: None,
+            };
+            match protocol.direction() {
+                Direction::Incoming => entry.incoming_servers.push(server),
+                Direction::Outgoing => entry.outgoing_servers.push(server),
+            }
+        }
+        entry
+    }
+
+    #[test]
+    fn test_report() {
+        use Protocol::*;
+        use Socket::*;
+        let lists = [
+            (
+                Source::DeltaChat,
+                vec![entry(
+                    Source::DeltaChat,
+                    "example.org",
+                    &[
+                        (Imap, "imap.example.org", 993, Ssl),
+                        (Smtp, "smtp.example.org", 465, Ssl),
+                    ],
+                )],
+            ),
+            (
+                Source::Mailspring,
+                vec![
+                    entry(
+                        Source::Mailspring,
+                        "Example.org",
+                        &[
+                            (Imap, "IMAP.example.org", 993, Ssl),
+                            (Smtp, "mail.example.org", 587, Starttls),
+                        ],
+                    ),
+                    entry(
+                        Source::Mailspring,
+                        "plain.example",
+                        &[(Imap, "plain.example", 143, Plain)],
+                    ),
+                ],
+            ),
+            (
+                Source::Mailcore,
+                vec![
+                    entry(
+                        Source::Mailcore,
+                        "example.org",
+                        &[(Smtp, "smtp.example.org", 465, Ssl)],
+                    ),
+                    entry(
+                        Source::Mailcore,
+                        "other.example",
+                        &[(Imap, "other.example", 993, Ssl)],
+                    ),
+                ],
+            ),
+        ];
+        let report = Report::new(&lists);
+        assert_eq!(report.domains.len(), 2);
+
+        let example = &report.domains[0];
+        assert_eq!(example.domain, "example.org");
+        assert_eq!(
+            example.findings[0],
+            Finding::MissingProtocol {
+                protocol: Imap,
+                present: vec![Source::DeltaChat, Source::Mailspring],
+                missing: vec![Source::Mailcore],
+            }
+        );
+        let kinds: Vec<&str> = example.findings.iter().map(Finding::kind).collect();
+        assert_eq!(kinds, ["missing_protocol", "hostname", "port", "socket"]);
+        assert_eq!(
+            example.findings[1],
+            Finding::Hostname {
+                protocol: Smtp,
+                values: BTreeMap::from([
+                    ("mail.example.org".to_string(), vec![Source::Mailspring]),
+                    (
+                        "smtp.example.org".to_string(),
+                        vec![Source::DeltaChat, Source::Mailcore]
+                    ),
+                ]),
+            }
+        );
+
+        assert_eq!(
+            report.domains[1].findings,
+            vec![Finding::PlaintextOnly {
+                protocol: Imap,
+                source: Source::Mailspring
+            }]
+        );
+
+        let mut table = Vec::new();
+        report.write_table(&mut table).unwrap();
+        let table = String::from_utf8(table).unwrap();
+        assert!(table.contains(
+            "Smtp: mail.example.org (mailspring), smtp.example.org (DeltaChat, mailcore)"
+        ));
+        assert!(table.contains("2 domains with findings"));
+    }
+
+    #[test]
+    fn test_bundled_lists() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../buildinlists");
+        let report = Report::from_lists_dir(&dir).unwrap();
+        // Nodemailer only carries SMTP, so it never misses IMAP.
+        assert!(!report
+            .domains
+            .iter()
+            .flat_map(|d| &d.findings)
+            .any(|f| matches!(
+                f,
+                Finding::MissingProtocol { missing, .. } if missing.contains(&Source::Nodemailer)
+            )));
+        let aol = report
+            .domains
+            .iter()
+            .find(|d| d.domain == "aol.com")
+            .unwrap();
+        assert!(aol.sources.len() >= 3);
+        assert!(aol.findings.iter().any(|f| matches!(
+            f,
+            Finding::Port {
+                protocol: Protocol::Smtp,
+                ..
+            }
+        )));
+        assert!(!report.domains.iter().any(|d| d.domain == "gmail.com"));
+        assert_eq!(aol.organization, "aol.com");
+        assert!(report.by_organization()["aol.com"].contains(&aol));
+    }
+}
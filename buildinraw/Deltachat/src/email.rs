@@ -0,0 +1,318 @@
+//! Email addresses.
+//!
+//! Counterpart of upstream `crate::tools::EmailAddress`, stricter about what
+//! it accepts: the local part is a dot-atom or a quoted string as in RFC 5322,
+//! with UTF-8 allowed as in RFC 6531, and the domain is converted to its
+//! ASCII form with UTS-46, so `user@Bücher.de` and `user@xn--bcher-kva.de`
+//! are the same address.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Serialize, Serializer};
+
+use crate::idn;
+
+/// Longest local part, in octets (RFC 5321 section 4.5.3.1.1).
+const MAX_LOCAL_PART: usize = 64;
+
+/// Longest domain, in octets, without trailing dot.
+const MAX_DOMAIN: usize = 253;
+
+/// Longest label of a domain, in octets.
+const MAX_LABEL: usize = 63;
+
+/// Separator of the subaddress in a local part, as in `user+tag@example.org`.
+const SUBADDRESS_SEPARATOR: char = '+';
+
+/// Why an email address is invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddressError {
+    /// The input is empty.
+    Empty,
+
+    /// There is no `@` separating local part and domain.
+    MissingAt,
+
+    /// Nothing before the `@`.
+    EmptyLocalPart,
+
+    /// Nothing after the `@`.
+    EmptyDomain,
+
+    /// The local part is longer than 64 octets.
+    LocalPartTooLong,
+
+    /// The domain is longer than 253 octets.
+    DomainTooLong,
+
+    /// The local part is neither a dot-atom nor a quoted string.
+    InvalidLocalPart(String),
+
+    /// The domain is not a valid (internationalized) domain name.
+    InvalidDomain(String),
+}
+
+impl fmt::Display for AddressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AddressError::Empty => write!(f, "empty email address"),
+            AddressError::MissingAt => write!(f, "email address must contain '@'"),
+            AddressError::EmptyLocalPart => write!(f, "missing local part before '@'"),
+            AddressError::EmptyDomain => write!(f, "missing domain after '@'"),
+            AddressError::LocalPartTooLong => {
+                write!(f, "local part is longer than {MAX_LOCAL_PART} octets")
+            }
+            AddressError::DomainTooLong => {
+                write!(f, "domain is longer than {MAX_DOMAIN} octets")
+            }
+            AddressError::InvalidLocalPart(local) => write!(f, "invalid local part {local:?}"),
+            AddressError::InvalidDomain(domain) => write!(f, "invalid domain {domain:?}"),
+        }
+    }
+}
+
+impl std::error::Error for AddressError {}
+
+/// Validated email address.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EmailAddress {
+    /// Local part as given, including the quotes of a quoted string.
+    pub local: String,
+
+    /// Domain in lowercase ASCII, with A-labels for internationalized labels.
+    pub domain: String,
+}
+
+impl EmailAddress {
+    /// Parses and validates an address such as `Alice+news@Bücher.de`.
+    ///
+    /// Surrounding whitespace and a trailing dot of the domain are ignored.
+    pub fn new(input: &str) -> Result<Self, AddressError> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(AddressError::Empty);
+        }
+        // A quoted local part may contain `@`, the domain may not.
+        let (local, domain) = input.rsplit_once('@').ok_or(AddressError::MissingAt)?;
+        if local.is_empty() {
+            return Err(AddressError::EmptyLocalPart);
+        }
+        if local.len() > MAX_LOCAL_PART {
+            return Err(AddressError::LocalPartTooLong);
+        }
+        if !is_dot_atom(local) && !is_quoted_string(local) {
+            return Err(AddressError::InvalidLocalPart(local.to_string()));
+        }
+        Ok(Self {
+            local: local.to_string(),
+            domain: normalize_domain(domain)?,
+        })
+    }
+
+    /// Whether the local part is a quoted string.
+    pub fn is_quoted(&self) -> bool {
+        self.local.starts_with('"')
+    }
+
+    /// Subaddress of the local part, `news` for `alice+news@example.org`.
+    ///
+    /// Quoted local parts have no subaddress, and neither do local parts
+    /// starting with the separator, such as `+news@example.org`.
+    pub fn subaddress(&self) -> Option<&str> {
+        if self.is_quoted() {
+            return None;
+        }
+        self.local
+            .split_once(SUBADDRESS_SEPARATOR)
+            .filter(|(user, _)| !user.is_empty())
+            .map(|(_, tag)| tag)
+    }
+
+    /// The address without subaddress, `alice@example.org` for
+    /// `alice+news@example.org`.
+    pub fn without_subaddress(&self) -> Self {
+        let local = match self.subaddress() {
+            Some(tag) => &self.local[..self.local.len() - tag.len() - 1],
+            None => &self.local,
+        };
+        Self {
+            local: local.to_string(),
+            domain: self.domain.clone(),
+        }
+    }
+
+    /// The address with the local part lowercased too.
+    ///
+    /// Local parts are case-sensitive by the RFCs but not in practice, so
+    /// this is the form to compare addresses by. Quoted local parts are kept
+    /// as they are.
+    pub fn normalized(&self) -> Self {
+        let local = if self.is_quoted() {
+            self.local.clone()
+        } else {
+            self.local.to_lowercase()
+        };
+        Self {
+            local,
+            domain: self.domain.clone(),
+        }
+    }
+
+    /// Domain with U-labels, `bücher.de` for `xn--bcher-kva.de`.
+    pub fn domain_unicode(&self) -> String {
+        idn::to_unicode(&self.domain)
+    }
+}
+
+impl FromStr for EmailAddress {
+    type Err = AddressError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl fmt::Display for EmailAddress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}@{}", self.local, self.domain)
+    }
+}
+
+impl Serialize for EmailAddress {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Converts a domain to lowercase ASCII with UTS-46 and checks its labels.
+fn normalize_domain(domain: &str) -> Result<String, AddressError> {
+    let invalid = || AddressError::InvalidDomain(domain.to_string());
+    let trimmed = domain.strip_suffix('.').unwrap_or(domain);
+    if trimmed.is_empty() {
+        return Err(AddressError::EmptyDomain);
+    }
+    let ascii = idn::to_ascii(trimmed).ok_or_else(invalid)?;
+    if ascii.len() > MAX_DOMAIN {
+        return Err(AddressError::DomainTooLong);
+    }
+    let valid_label = |label: &str| {
+        !label.is_empty()
+            && label.len() <= MAX_LABEL
+            && !label.starts_with('-')
+            && !label.ends_with('-')
+            && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    };
+    if !ascii.split('.').all(valid_label) {
+        return Err(invalid());
+    }
+    Ok(ascii)
+}
+
+/// `atext` of RFC 5322, plus UTF-8 as in RFC 6531.
+fn is_atext(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "!#$%&'*+-/=?^_`{|}~".contains(c) || !c.is_ascii()
+}
+
+fn is_dot_atom(local: &str) -> bool {
+    local
+        .split('.')
+        .all(|atom| !atom.is_empty() && atom.chars().all(is_atext))
+}
+
+/// Quoted string of printable characters and spaces, with `\` escaping the
+/// next character.
+fn is_quoted_string(local: &str) -> bool {
+    let Some(inner) = local.strip_prefix('"').and_then(|l| l.strip_suffix('"')) else {
+        return false;
+    };
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(c) if !c.is_ascii_control() => {}
+                _ => return false,
+            },
+            '"' => return false,
+            c if c.is_ascii_control() => return false,
+            _ => {}
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let addr = EmailAddress::new(" Alice+News@Bücher.DE. ").unwrap();
+        assert_eq!(addr.local, "Alice+News");
+        assert_eq!(addr.domain, "xn--bcher-kva.de");
+        assert_eq!(addr.domain_unicode(), "bücher.de");
+        assert_eq!(addr.to_string(), "Alice+News@xn--bcher-kva.de");
+        assert_eq!(
+            EmailAddress::new("alice+news@xn--bcher-kva.de").unwrap(),
+            addr.normalized()
+        );
+
+        let quoted: EmailAddress = r#""a@b c\"d"@example.org"#.parse().unwrap();
+        assert!(quoted.is_quoted());
+        assert_eq!(quoted.domain, "example.org");
+        assert_eq!(quoted.subaddress(), None);
+        assert_eq!(EmailAddress::new("用户@例子.广告").unwrap().local, "用户");
+    }
+
+    #[test]
+    fn test_subaddress() {
+        let addr = EmailAddress::new("bob+tag+more@example.org").unwrap();
+        assert_eq!(addr.subaddress(), Some("tag+more"));
+        assert_eq!(addr.without_subaddress().to_string(), "bob@example.org");
+        let plain = EmailAddress::new("bob@example.org").unwrap();
+        assert_eq!(plain.without_subaddress(), plain);
+        let leading = EmailAddress::new("+news@example.org").unwrap();
+        assert_eq!(leading.subaddress(), None);
+        assert_eq!(leading.without_subaddress(), leading);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(EmailAddress::new(" "), Err(AddressError::Empty));
+        assert_eq!(
+            EmailAddress::new("example.org"),
+            Err(AddressError::MissingAt)
+        );
+        assert_eq!(
+            EmailAddress::new("@example.org"),
+            Err(AddressError::EmptyLocalPart)
+        );
+        assert_eq!(EmailAddress::new("bob@"), Err(AddressError::EmptyDomain));
+        assert_eq!(
+            EmailAddress::new(&format!("{}@example.org", "a".repeat(65))),
+            Err(AddressError::LocalPartTooLong)
+        );
+        assert!(matches!(
+            EmailAddress::new("bob..smith@example.org"),
+            Err(AddressError::InvalidLocalPart(_))
+        ));
+        assert!(matches!(
+            EmailAddress::new("a\"b@example.org"),
+            Err(AddressError::InvalidLocalPart(_))
+        ));
+        for domain in [
+            "exa mple.org",
+            "example..org",
+            "-example.org",
+            "[192.0.2.1]",
+        ] {
+            assert!(
+                matches!(
+                    EmailAddress::new(&format!("bob@{domain}")),
+                    Err(AddressError::InvalidDomain(_))
+                ),
+                "{domain}"
+            );
+        }
+    }
+}
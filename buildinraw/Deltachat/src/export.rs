@@ -0,0 +1,62 @@
+//! Renderers turning provider records into the formats mail clients fetch.
+
+pub mod autodiscover;
+pub mod srv;
+pub mod thunderbird;
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+
+use crate::provider::{Provider, ProviderDb};
+
+/// Escapes text for use in XML content and attribute values.
+pub(crate) fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Appends `<tag>text</tag>` on its own line.
+pub(crate) fn element(out: &mut String, indent: usize, tag: &str, text: &str) {
+    writeln!(out, "{:indent$}<{tag}>{}</{tag}>", "", escape(text)).unwrap();
+}
+
+/// Writes `<id>.xml` rendered by `render` for every provider of the database
+/// into `dir`.
+///
+/// Providers are only written if `wants` accepts one of their domains, and
+/// only accepted domains are passed to `render`.
+pub(crate) fn write_dir(
+    db: &ProviderDb,
+    dir: &Path,
+    mut wants: impl FnMut(&str, &Provider) -> bool,
+    render: impl Fn(&Provider, Vec<&str>) -> String,
+) -> Result<usize> {
+    std::fs::create_dir_all(dir).with_context(|| format!("cannot create {}", dir.display()))?;
+    let mut written = 0;
+    for provider in db.providers() {
+        let domains: Vec<&str> = db
+            .domains_of(provider)
+            .filter(|domain| wants(domain, provider))
+            .collect();
+        if domains.is_empty() {
+            continue;
+        }
+        let path = dir.join(format!("{}.xml", provider.id));
+        std::fs::write(&path, render(provider, domains))
+            .with_context(|| format!("cannot write {}", path.display()))?;
+        written += 1;
+    }
+    Ok(written)
+}
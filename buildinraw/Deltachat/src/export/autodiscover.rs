@@ -0,0 +1,246 @@
+//! Microsoft Autodiscover POX responses.
+//!
+//! See [MS-OXDSCLI] section 2.2.4 and `parse_autodiscover` in
+//! `autodiscover.py`, which reads these documents.
+//!
+//! [MS-OXDSCLI]: https://learn.microsoft.com/en-us/openspecs/exchange_server_protocols/ms-oxdscli/
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::Result;
+
+use super::element;
+use crate::provider::{Protocol, Provider, ProviderDb, Server, Socket};
+use crate::schema::Authentication;
+
+/// Namespace of the `Autodiscover` root element.
+pub const RESPONSE_NS: &str =
+    "http://schemas.microsoft.com/exchange/autodiscover/responseschema/2006";
+
+/// Namespace of the Outlook `Response` element.
+pub const OUTLOOK_NS: &str =
+    "http://schemas.microsoft.com/exchange/autodiscover/outlook/responseschema/2006a";
+
+/// `Type` of a protocol; JMAP and ActiveSync have none, ActiveSync being
+/// configured by the separate `mobilesync` response schema.
+fn protocol_type(protocol: Protocol) -> Option<&'static str> {
+    match protocol {
+        Protocol::Imap => Some("IMAP"),
+        Protocol::Pop3 => Some("POP3"),
+        Protocol::Smtp => Some("SMTP"),
+        Protocol::Ews => Some("EXCH"),
+        Protocol::Jmap | Protocol::ActiveSync => None,
+    }
+}
+
+/// `SSL` and `Encryption` values; clients prefer `Encryption` if present.
+fn encryption(socket: Socket) -> (&'static str, &'static str) {
+    match socket {
+        Socket::Ssl => ("on", "SSL"),
+        Socket::Starttls => ("on", "TLS"),
+        Socket::Automatic => ("on", "Auto"),
+        Socket::Plain => ("off", "None"),
+    }
+}
+
+/// `SPA` value: `on` if the server takes Secure Password Authentication,
+/// NTLM or its Kerberos variant through GSSAPI.
+fn spa(authentication: &[Authentication]) -> &'static str {
+    if authentication
+        .iter()
+        .any(|a| matches!(a, Authentication::Ntlm | Authentication::Gssapi))
+    {
+        "on"
+    } else {
+        "off"
+    }
+}
+
+/// Wraps the `Account` element content into a complete response.
+fn response(account: &str) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    writeln!(out, "<Autodiscover xmlns=\"{RESPONSE_NS}\">").unwrap();
+    writeln!(out, "  <Response xmlns=\"{OUTLOOK_NS}\">").unwrap();
+    out.push_str("    <Account>\n");
+    element(&mut out, 6, "AccountType", "email");
+    out.push_str(account);
+    out.push_str("    </Account>\n");
+    out.push_str("  </Response>\n");
+    out.push_str("</Autodiscover>\n");
+    out
+}
+
+fn protocol(
+    out: &mut String,
+    server: &Server,
+    authentication: &[Authentication],
+    email: Option<&str>,
+) {
+    let Some(protocol_type) = protocol_type(server.protocol) else {
+        return;
+    };
+    let (ssl, encryption) = encryption(server.socket);
+    out.push_str("      <Protocol>\n");
+    element(out, 8, "Type", protocol_type);
+    element(out, 8, "Server", &server.hostname);
+    element(out, 8, "Port", &server.port.to_string());
+    if server.protocol == Protocol::Ews {
+        let url = format!("https://{}/EWS/Exchange.asmx", server.hostname);
+        element(out, 8, "EwsUrl", &url);
+    }
+    if let Some(email) = email {
+        element(out, 8, "LoginName", server.username(email));
+    }
+    element(out, 8, "SPA", spa(authentication));
+    element(out, 8, "SSL", ssl);
+    element(out, 8, "Encryption", encryption);
+    if server.protocol == Protocol::Smtp {
+        element(out, 8, "AuthRequired", "on");
+    }
+    out.push_str("      </Protocol>\n");
+}
+
+/// Renders the `settings` response of a provider.
+///
+/// Servers whose protocol has no `Protocol` type are left out.
+/// `LoginName` is derived from `email` with the username pattern of each
+/// server, and left out if no address is given.
+pub fn settings(provider: &Provider, email: Option<&str>) -> String {
+    let mut account = String::new();
+    element(&mut account, 6, "Action", "settings");
+    let authentication = provider.authentication();
+    for server in &provider.server {
+        protocol(&mut account, server, &authentication, email);
+    }
+    response(&account)
+}
+
+/// Renders a `redirectAddr` response, telling the client to restart
+/// Autodiscover with another email address.
+pub fn redirect_addr(email: &str) -> String {
+    let mut account = String::new();
+    element(&mut account, 6, "Action", "redirectAddr");
+    element(&mut account, 6, "RedirectAddr", email);
+    response(&account)
+}
+
+/// Renders a `redirectUrl` response, telling the client to send the same
+/// request to another URL.
+pub fn redirect_url(url: &str) -> String {
+    let mut account = String::new();
+    element(&mut account, 6, "Action", "redirectUrl");
+    element(&mut account, 6, "RedirectUrl", url);
+    response(&account)
+}
+
+/// Writes the `settings` response of every provider into `dir` as
+/// `<id>.xml`, without `LoginName`.
+///
+/// Providers are only written if `wants` accepts one of their domains.
+pub fn write_dir(
+    db: &ProviderDb,
+    dir: &Path,
+    wants: impl FnMut(&str, &Provider) -> bool,
+) -> Result<usize> {
+    super::write_dir(db, dir, wants, |provider, _| settings(provider, None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn account_of<'a>(doc: &'a roxmltree::Document) -> roxmltree::Node<'a, 'a> {
+        let root = doc.root_element();
+        assert_eq!(root.tag_name().namespace(), Some(RESPONSE_NS));
+        let response = root.first_element_child().unwrap();
+        assert!(response.has_tag_name((OUTLOOK_NS, "Response")));
+        let account = response.first_element_child().unwrap();
+        assert!(account.has_tag_name((OUTLOOK_NS, "Account")));
+        account
+    }
+
+    fn text<'a>(node: roxmltree::Node<'a, 'a>, tag: &str) -> Option<&'a str> {
+        node.children()
+            .find(|n| n.has_tag_name((OUTLOOK_NS, tag)))
+            .and_then(|n| n.text())
+    }
+
+    #[test]
+    fn test_settings() {
+        let db = ProviderDb::bundled();
+        let nauta = db.get_provider_by_id("nauta.cu").unwrap();
+        let xml = settings(nauta, Some("alice@nauta.cu"));
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let account = account_of(&doc);
+        assert_eq!(text(account, "Action"), Some("settings"));
+
+        let protocols: Vec<_> = account
+            .children()
+            .filter(|n| n.has_tag_name((OUTLOOK_NS, "Protocol")))
+            .collect();
+        assert_eq!(protocols.len(), 2);
+        assert_eq!(text(protocols[0], "Type"), Some("IMAP"));
+        assert_eq!(text(protocols[0], "Server"), Some("imap.nauta.cu"));
+        assert_eq!(text(protocols[0], "Port"), Some("143"));
+        assert_eq!(text(protocols[0], "Encryption"), Some("TLS"));
+        assert_eq!(text(protocols[0], "LoginName"), Some("alice@nauta.cu"));
+        assert_eq!(text(protocols[0], "SPA"), Some("off"));
+        assert_eq!(text(protocols[1], "Type"), Some("SMTP"));
+
+        let xml = settings(nauta, None);
+        assert!(!xml.contains("LoginName"));
+
+        let mut exchange = nauta.clone();
+        for protocol in [Protocol::Ews, Protocol::Jmap] {
+            let mut server = nauta.server[0].clone();
+            server.protocol = protocol;
+            server.hostname = "mail.nauta.cu".to_string();
+            exchange.server.push(server);
+        }
+        let xml = settings(&exchange, None);
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let protocols: Vec<_> = account_of(&doc)
+            .children()
+            .filter(|n| n.has_tag_name((OUTLOOK_NS, "Protocol")))
+            .collect();
+        assert_eq!(protocols.len(), 3);
+        assert_eq!(text(protocols[2], "Type"), Some("EXCH"));
+        assert_eq!(
+            text(protocols[2], "EwsUrl"),
+            Some("https://mail.nauta.cu/EWS/Exchange.asmx")
+        );
+    }
+
+    #[test]
+    fn test_spa() {
+        assert_eq!(spa(&[Authentication::PasswordCleartext]), "off");
+        assert_eq!(spa(&[]), "off");
+        assert_eq!(spa(&[Authentication::OAuth2, Authentication::Ntlm]), "on");
+        assert_eq!(spa(&[Authentication::Gssapi]), "on");
+        assert_eq!(
+            Authentication::from_autodiscover_spa(spa(&[Authentication::Ntlm])),
+            Some(Authentication::Ntlm)
+        );
+    }
+
+    #[test]
+    fn test_redirects() {
+        let xml = redirect_addr("bob@example.org");
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let account = account_of(&doc);
+        assert_eq!(text(account, "Action"), Some("redirectAddr"));
+        assert_eq!(text(account, "RedirectAddr"), Some("bob@example.org"));
+
+        let xml =
+            redirect_url("https://autodiscover.example.org/autodiscover/autodiscover.xml?a=1&b=2");
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let account = account_of(&doc);
+        assert_eq!(text(account, "Action"), Some("redirectUrl"));
+        assert_eq!(
+            text(account, "RedirectUrl"),
+            Some("https://autodiscover.example.org/autodiscover/autodiscover.xml?a=1&b=2")
+        );
+    }
+}
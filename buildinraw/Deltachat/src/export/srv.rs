@@ -0,0 +1,170 @@
+//! RFC 6186 SRV records, with the implicit TLS services of RFC 8314.
+//!
+//! The records are written as a BIND zone file snippet with absolute owner
+//! names, so they can be appended to any zone, e.g. for `srv.py`:
+//!
+//! ```text
+//! _imaps._tcp.example.org. IN SRV 0 1 993 imap.example.org.
+//! _imap._tcp.example.org. IN SRV 0 0 0 .
+//! ```
+
+use std::fmt::Write as _;
+
+use crate::provider::{Protocol, Provider, Server, Socket};
+
+/// SRV record of a domain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SrvRecord {
+    /// Service label including the protocol, e.g. `_imaps._tcp`.
+    pub service: &'static str,
+    pub priority: u16,
+    pub weight: u16,
+    pub port: u16,
+
+    /// Target hostname, `.` if the service is not offered.
+    pub target: String,
+}
+
+/// Implicit TLS and STARTTLS service of a protocol. JMAP (RFC 8620) only has
+/// the former, the Exchange protocols have no SRV records.
+fn services(protocol: Protocol) -> Option<(&'static str, Option<&'static str>)> {
+    match protocol {
+        Protocol::Imap => Some(("_imaps._tcp", Some("_imap._tcp"))),
+        Protocol::Pop3 => Some(("_pop3s._tcp", Some("_pop3._tcp"))),
+        Protocol::Smtp => Some(("_submissions._tcp", Some("_submission._tcp"))),
+        Protocol::Jmap => Some(("_jmap._tcp", None)),
+        Protocol::Ews | Protocol::ActiveSync => None,
+    }
+}
+
+/// Whether a server uses implicit TLS; an automatic socket is guessed from
+/// the port.
+fn implicit_tls(server: &Server) -> bool {
+    match server.socket {
+        Socket::Ssl => true,
+        Socket::Starttls | Socket::Plain => false,
+        Socket::Automatic => matches!(server.port, 443 | 465 | 993 | 995),
+    }
+}
+
+/// SRV records implied by the servers of a provider.
+///
+/// Priorities follow the order of the `server` entries. Protocols offered
+/// with implicit TLS only get a `.` record for their STARTTLS service, so
+/// clients do not fall back to it.
+pub fn records(provider: &Provider) -> Vec<SrvRecord> {
+    let mut records: Vec<SrvRecord> = provider
+        .server
+        .iter()
+        .zip(0..)
+        .filter_map(|(server, priority)| {
+            let (tls, starttls) = services(server.protocol)?;
+            let service = match starttls {
+                Some(starttls) if !implicit_tls(server) => starttls,
+                _ => tls,
+            };
+            Some(SrvRecord {
+                service,
+                priority,
+                weight: 1,
+                port: server.port,
+                target: server.hostname.clone(),
+            })
+        })
+        .collect();
+
+    for protocol in [Protocol::Imap, Protocol::Pop3, Protocol::Smtp] {
+        let Some((tls, Some(starttls))) = services(protocol) else {
+            continue;
+        };
+        let offered = |service| records.iter().any(|r| r.service == service);
+        if offered(tls) && !offered(starttls) {
+            records.push(SrvRecord {
+                service: starttls,
+                priority: 0,
+                weight: 0,
+                port: 0,
+                target: ".".to_string(),
+            });
+        }
+    }
+    records
+}
+
+/// Renders the SRV records of a provider for `domain`.
+///
+/// Wildcard domains such as `*.hermes.radio` cannot have SRV records below
+/// them and yield an empty snippet.
+pub fn zone(domain: &str, provider: &Provider) -> String {
+    let mut out = String::new();
+    if domain.starts_with('*') {
+        return out;
+    }
+    for record in records(provider) {
+        let target = if record.target == "." {
+            record.target
+        } else {
+            format!("{}.", record.target)
+        };
+        writeln!(
+            out,
+            "{}.{domain}. IN SRV {} {} {} {target}",
+            record.service, record.priority, record.weight, record.port
+        )
+        .unwrap();
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::ProviderDb;
+
+    #[test]
+    fn test_zone() {
+        let db = ProviderDb::bundled();
+        let gmail = db.get_provider_by_id("gmail").unwrap();
+        assert_eq!(
+            zone("gmail.com", gmail),
+            "_imaps._tcp.gmail.com. IN SRV 0 1 993 imap.gmail.com.
+_submissions._tcp.gmail.com. IN SRV 1 1 465 smtp.gmail.com.
+_imap._tcp.gmail.com. IN SRV 0 0 0 .
+_submission._tcp.gmail.com. IN SRV 0 0 0 .
+"
+        );
+
+        let hermes = db.get_provider_by_id("hermes.radio").unwrap();
+        assert!(zone("*.hermes.radio", hermes).is_empty());
+    }
+
+    #[test]
+    fn test_starttls_records() {
+        let db = ProviderDb::bundled();
+        let nauta = db.get_provider_by_id("nauta.cu").unwrap();
+        let records = records(nauta);
+        assert!(records.iter().all(|r| r.target != "."));
+        assert_eq!(records[0].service, "_imap._tcp");
+        assert_eq!(records[0].port, 143);
+    }
+
+    #[test]
+    fn test_jmap_and_exchange() {
+        let db = ProviderDb::bundled();
+        let mut provider = db.get_provider_by_id("nauta.cu").unwrap().clone();
+        for (protocol, hostname) in [
+            (Protocol::Jmap, "jmap.nauta.cu"),
+            (Protocol::Ews, "mail.nauta.cu"),
+        ] {
+            let mut server = provider.server[0].clone();
+            server.protocol = protocol;
+            server.hostname = hostname.to_string();
+            server.socket = Socket::Automatic;
+            server.port = 443;
+            provider.server.push(server);
+        }
+        let zone = zone("nauta.cu", &provider);
+        assert!(zone.contains("_jmap._tcp.nauta.cu. IN SRV 2 1 443 jmap.nauta.cu.\n"));
+        assert!(!zone.contains("mail.nauta.cu"));
+    }
+}
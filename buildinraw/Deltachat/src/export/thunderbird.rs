@@ -0,0 +1,202 @@
+//! Thunderbird autoconfig `config-v1.1.xml`.
+//!
+//! See <https://wiki.mozilla.org/Thunderbird:Autoconfiguration:ConfigFileFormat>.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+use anyhow::Result;
+
+use super::{element, escape};
+use crate::provider::{Direction, Protocol, Provider, ProviderDb, Server, Socket};
+use crate::schema::OAuth2Descriptor;
+
+fn server_type(protocol: Protocol) -> &'static str {
+    match protocol {
+        Protocol::Imap => "imap",
+        Protocol::Pop3 => "pop3",
+        Protocol::Smtp => "smtp",
+        Protocol::Jmap => "jmap",
+        Protocol::Ews => "exchange",
+        Protocol::ActiveSync => "activesync",
+    }
+}
+
+/// Thunderbird has no automatic socket type; it is guessed from the port.
+fn socket_type(server: &Server) -> &'static str {
+    match server.socket {
+        Socket::Ssl => "SSL",
+        Socket::Starttls => "STARTTLS",
+        Socket::Plain => "plain",
+        Socket::Automatic => match server.port {
+            465 | 993 | 995 => "SSL",
+            _ => "STARTTLS",
+        },
+    }
+}
+
+/// Writes the `<oAuth2>` block; the issuer is the host of the authorization
+/// endpoint.
+fn oauth2(out: &mut String, oauth2: &OAuth2Descriptor) {
+    let issuer = oauth2
+        .authorization_endpoint
+        .split("://")
+        .nth(1)
+        .and_then(|rest| rest.split('/').next())
+        .unwrap_or_default();
+    out.push_str("  <oAuth2>\n");
+    element(out, 4, "issuer", issuer);
+    element(out, 4, "scope", &oauth2.scopes.join(" "));
+    element(out, 4, "authURL", &oauth2.authorization_endpoint);
+    element(out, 4, "tokenURL", &oauth2.token_endpoint);
+    out.push_str("  </oAuth2>\n");
+}
+
+/// Renders the `config-v1.1.xml` of a provider for the given domains.
+///
+/// Wildcard domains such as `*.hermes.radio` have no equivalent in the
+/// format and are left out.
+pub fn config_v11<'a>(provider: &Provider, domains: impl IntoIterator<Item = &'a str>) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<clientConfig version=\"1.1\">\n");
+    writeln!(out, "  <emailProvider id=\"{}\">", escape(&provider.id)).unwrap();
+    for domain in domains.into_iter().filter(|d| !d.starts_with('*')) {
+        element(&mut out, 4, "domain", domain);
+    }
+    element(&mut out, 4, "displayName", &provider.id);
+    element(&mut out, 4, "displayShortName", &provider.id);
+
+    for server in &provider.server {
+        let tag = match server.protocol.direction() {
+            Direction::Incoming => "incomingServer",
+            Direction::Outgoing => "outgoingServer",
+        };
+        writeln!(out, "    <{tag} type=\"{}\">", server_type(server.protocol)).unwrap();
+        element(&mut out, 6, "hostname", &server.hostname);
+        element(&mut out, 6, "port", &server.port.to_string());
+        element(&mut out, 6, "socketType", socket_type(server));
+        for authentication in provider.authentication() {
+            element(
+                &mut out,
+                6,
+                "authentication",
+                authentication.thunderbird_value(),
+            );
+        }
+        element(
+            &mut out,
+            6,
+            "username",
+            server.username_pattern.placeholder(),
+        );
+        writeln!(out, "    </{tag}>").unwrap();
+    }
+
+    writeln!(
+        out,
+        "    <documentation url=\"{}\">",
+        escape(&provider.overview_page)
+    )
+    .unwrap();
+    let descr = if provider.before_login_hint.is_empty() {
+        "Delta Chat provider overview"
+    } else {
+        provider.before_login_hint.as_str()
+    };
+    writeln!(out, "      <descr lang=\"en\">{}</descr>", escape(descr)).unwrap();
+    out.push_str("    </documentation>\n");
+    out.push_str("  </emailProvider>\n");
+    if let Some(authorizer) = provider.oauth2_authorizer {
+        oauth2(&mut out, &authorizer.descriptor());
+    }
+    out.push_str("</clientConfig>\n");
+    out
+}
+
+/// Writes `<id>.xml` for every provider of the database into `dir`.
+///
+/// Providers are only written if `wants` accepts one of their domains, and
+/// only accepted domains are listed.
+pub fn write_dir(
+    db: &ProviderDb,
+    dir: &Path,
+    wants: impl FnMut(&str, &Provider) -> bool,
+) -> Result<usize> {
+    super::write_dir(db, dir, wants, |provider, domains| {
+        config_v11(provider, domains)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_v11() {
+        let db = ProviderDb::bundled();
+        let gmail = db.get_provider_by_id("gmail").unwrap();
+        let xml = config_v11(gmail, db.domains_of(gmail));
+        let doc = roxmltree::Document::parse(&xml).unwrap();
+        let provider = doc.root_element().first_element_child().unwrap();
+        assert_eq!(provider.attribute("id"), Some("gmail"));
+
+        let domains: Vec<&str> = provider
+            .children()
+            .filter(|n| n.has_tag_name("domain"))
+            .filter_map(|n| n.text())
+            .collect();
+        assert_eq!(domains, ["gmail.com", "googlemail.com", "google.com"]);
+
+        let incoming = provider
+            .children()
+            .find(|n| n.has_tag_name("incomingServer"))
+            .unwrap();
+        assert_eq!(incoming.attribute("type"), Some("imap"));
+        let text = |tag: &str| {
+            incoming
+                .children()
+                .find(|n| n.has_tag_name(tag))
+                .and_then(|n| n.text())
+        };
+        assert_eq!(text("hostname"), Some("imap.gmail.com"));
+        assert_eq!(text("socketType"), Some("SSL"));
+        assert_eq!(text("authentication"), Some("OAuth2"));
+        assert_eq!(text("username"), Some("%EMAILADDRESS%"));
+
+        let documentation = provider
+            .children()
+            .find(|n| n.has_tag_name("documentation"))
+            .unwrap();
+        assert_eq!(
+            documentation.attribute("url"),
+            Some("https://providers.delta.chat/gmail")
+        );
+
+        let oauth2 = doc
+            .root_element()
+            .children()
+            .find(|n| n.has_tag_name("oAuth2"))
+            .unwrap();
+        let text = |tag: &str| {
+            oauth2
+                .children()
+                .find(|n| n.has_tag_name(tag))
+                .and_then(|n| n.text())
+        };
+        assert_eq!(text("issuer"), Some("accounts.google.com"));
+        assert_eq!(text("scope"), Some("https://mail.google.com/ email"));
+    }
+
+    #[test]
+    fn test_all_providers_are_valid_xml() {
+        let db = ProviderDb::bundled();
+        for provider in db.providers() {
+            let xml = config_v11(provider, db.domains_of(provider));
+            roxmltree::Document::parse(&xml).unwrap_or_else(|e| panic!("{}: {e}", provider.id));
+        }
+
+        let hermes = db.get_provider_by_id("hermes.radio").unwrap();
+        assert!(!config_v11(hermes, db.domains_of(hermes)).contains("*."));
+    }
+}
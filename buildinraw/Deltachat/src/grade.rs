@@ -0,0 +1,351 @@
+//! Security grading of provider configurations.
+//!
+//! Every server starts with a score of 100 and loses the penalty of each
+//! [`Issue`] it has; the score is then mapped to a [`Grade`] with the
+//! thresholds of the [`GradingRules`]. An entry or provider gets the grade of
+//! its worst server. Penalties and thresholds can be loaded from a YAML or
+//! JSON file, so grades are reproducible for a given rules file:
+//!
+//! ```yaml
+//! penalties:
+//!   starttls: 10
+//!   no_strict_tls: 50
+//! thresholds:
+//!   a: 95
+//! ```
+//!
+//! Values missing from the file keep their defaults.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::provider::{Protocol, Socket};
+use crate::schema::{Authentication, Entry, MailServer};
+
+/// Security issue of a server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Issue {
+    /// STARTTLS instead of implicit TLS, open to stripping attacks.
+    Starttls,
+
+    /// No TLS at all.
+    Plaintext,
+
+    /// Socket security left to the client to probe.
+    AutomaticSocket,
+
+    /// Port other than the standard ones for the protocol and socket.
+    NonStandardPort,
+
+    /// Certificates are not checked strictly (`strict_tls: false`).
+    NoStrictTls,
+
+    /// Password sent as is, e.g. with `AUTH PLAIN` or `LOGIN`.
+    CleartextPassword,
+}
+
+/// Security grade, `A` being best.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Grade {
+    A,
+    B,
+    C,
+    D,
+    F,
+}
+
+/// Lowest score for each grade; anything below `d` is `F`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Thresholds {
+    pub a: u32,
+    pub b: u32,
+    pub c: u32,
+    pub d: u32,
+}
+
+impl Default for Thresholds {
+    fn default() -> Self {
+        Self {
+            a: 90,
+            b: 75,
+            c: 50,
+            d: 25,
+        }
+    }
+}
+
+/// Penalties and thresholds used for grading.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GradingRules {
+    /// Score deducted for each issue.
+    pub penalties: BTreeMap<Issue, u32>,
+
+    /// Lowest score for each grade.
+    pub thresholds: Thresholds,
+}
+
+impl Default for GradingRules {
+    fn default() -> Self {
+        Self {
+            penalties: BTreeMap::from([
+                (Issue::Starttls, 10),
+                (Issue::Plaintext, 60),
+                (Issue::AutomaticSocket, 20),
+                (Issue::NonStandardPort, 5),
+                (Issue::NoStrictTls, 30),
+                (Issue::CleartextPassword, 5),
+            ]),
+            thresholds: Thresholds::default(),
+        }
+    }
+}
+
+impl GradingRules {
+    /// Parses rules from YAML or JSON, keeping the default penalty of every
+    /// issue not listed. Unknown keys are rejected rather than ignored.
+    pub fn from_yaml(src: &str) -> Result<Self> {
+        let rules: GradingRules = serde_yaml::from_str(src)?;
+        let mut penalties = Self::default().penalties;
+        penalties.extend(rules.penalties);
+        Ok(Self {
+            penalties,
+            thresholds: rules.thresholds,
+        })
+    }
+
+    /// Reads rules from a YAML or JSON file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let src = std::fs::read_to_string(path)
+            .with_context(|| format!("cannot read {}", path.display()))?;
+        Self::from_yaml(&src).with_context(|| format!("cannot parse {}", path.display()))
+    }
+
+    fn grade(&self, score: u32) -> Grade {
+        let t = &self.thresholds;
+        match score {
+            s if s >= t.a => Grade::A,
+            s if s >= t.b => Grade::B,
+            s if s >= t.c => Grade::C,
+            s if s >= t.d => Grade::D,
+            _ => Grade::F,
+        }
+    }
+
+    /// Grades one server. `strict_tls` is the provider option of that name.
+    pub fn grade_server(&self, server: &MailServer, strict_tls: bool) -> ServerGrade {
+        let mut issues = Vec::new();
+        match server.socket_type {
+            Socket::Ssl => {}
+            Socket::Starttls => issues.push(Issue::Starttls),
+            Socket::Plain => issues.push(Issue::Plaintext),
+            Socket::Automatic => issues.push(Issue::AutomaticSocket),
+        }
+        if !standard_ports(server.protocol, server.socket_type).contains(&server.port) {
+            issues.push(Issue::NonStandardPort);
+        }
+        if !strict_tls {
+            issues.push(Issue::NoStrictTls);
+        }
+        if server
+            .authentication
+            .contains(&Authentication::PasswordCleartext)
+        {
+            issues.push(Issue::CleartextPassword);
+        }
+
+        let penalty: u32 = issues
+            .iter()
+            .map(|i| self.penalties.get(i).copied().unwrap_or_default())
+            .sum();
+        let score = 100u32.saturating_sub(penalty);
+        ServerGrade {
+            protocol: server.protocol,
+            hostname: server.hostname.clone(),
+            port: server.port,
+            grade: self.grade(score),
+            score,
+            issues,
+        }
+    }
+
+    /// Grades all servers of an entry.
+    pub fn grade_entry(&self, entry: &Entry, strict_tls: bool) -> EntryGrade {
+        let servers: Vec<ServerGrade> = entry
+            .servers()
+            .map(|server| self.grade_server(server, strict_tls))
+            .collect();
+        let worst = servers.iter().min_by_key(|s| s.score);
+        EntryGrade {
+            grade: worst.map_or(Grade::F, |s| s.grade),
+            score: worst.map_or(0, |s| s.score),
+            servers,
+        }
+    }
+}
+
+/// Standard ports of a protocol for a socket type.
+fn standard_ports(protocol: Protocol, socket: Socket) -> &'static [u16] {
+    match (protocol, socket) {
+        (Protocol::Imap, Socket::Ssl) => &[993],
+        (Protocol::Imap, Socket::Starttls | Socket::Plain) => &[143],
+        (Protocol::Imap, Socket::Automatic) => &[993, 143],
+        (Protocol::Pop3, Socket::Ssl) => &[995],
+        (Protocol::Pop3, Socket::Starttls | Socket::Plain) => &[110],
+        (Protocol::Pop3, Socket::Automatic) => &[995, 110],
+        (Protocol::Smtp, Socket::Ssl) => &[465],
+        (Protocol::Smtp, Socket::Starttls | Socket::Plain) => &[587, 25],
+        (Protocol::Smtp, Socket::Automatic) => &[465, 587, 25],
+        (Protocol::Jmap | Protocol::Ews | Protocol::ActiveSync, Socket::Ssl) => &[443],
+        (
+            Protocol::Jmap | Protocol::Ews | Protocol::ActiveSync,
+            Socket::Starttls | Socket::Plain,
+        ) => &[80],
+        (Protocol::Jmap | Protocol::Ews | Protocol::ActiveSync, Socket::Automatic) => &[443, 80],
+    }
+}
+
+/// Grade of one server.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ServerGrade {
+    pub protocol: Protocol,
+    pub hostname: String,
+    pub port: u16,
+    pub grade: Grade,
+
+    /// 100 minus the penalties of the issues.
+    pub score: u32,
+    pub issues: Vec<Issue>,
+}
+
+/// Grade of an entry, that of its worst server.
+///
+/// Entries without servers get `F`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EntryGrade {
+    pub grade: Grade,
+    pub score: u32,
+    pub servers: Vec<ServerGrade>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::buildin::Source;
+    use crate::provider::ProviderDb;
+    use crate::schema::read_list;
+
+    fn server(protocol: Protocol, port: u16, socket_type: Socket) -> MailServer {
+        MailServer {
+            protocol,
+            hostname: "mail.example.org".to_string(),
+            port,
+            socket_type,
+            authentication: Vec::new(),
+            username: None,
+        }
+    }
+
+    #[test]
+    fn test_grade_server() {
+        let rules = GradingRules::default();
+        let grade = rules.grade_server(&server(Protocol::Imap, 993, Socket::Ssl), true);
+        assert_eq!((grade.grade, grade.score), (Grade::A, 100));
+
+        let grade = rules.grade_server(&server(Protocol::Smtp, 587, Socket::Starttls), true);
+        assert_eq!(grade.issues, [Issue::Starttls]);
+        assert_eq!(grade.grade, Grade::A);
+
+        let mut plain = server(Protocol::Imap, 1143, Socket::Plain);
+        plain.authentication = vec![Authentication::PasswordCleartext];
+        let grade = rules.grade_server(&plain, false);
+        assert_eq!(
+            grade.issues,
+            [
+                Issue::Plaintext,
+                Issue::NonStandardPort,
+                Issue::NoStrictTls,
+                Issue::CleartextPassword
+            ]
+        );
+        assert_eq!((grade.grade, grade.score), (Grade::F, 0));
+    }
+
+    #[test]
+    fn test_rules_file() {
+        let rules =
+            GradingRules::from_yaml("penalties:\n  starttls: 20\nthresholds:\n  a: 95\n").unwrap();
+        assert_eq!(rules.penalties[&Issue::Starttls], 20);
+        assert_eq!(rules.penalties[&Issue::Plaintext], 60);
+        assert_eq!(rules.thresholds.a, 95);
+        assert_eq!(rules.thresholds.b, 75);
+        let grade = rules.grade_server(&server(Protocol::Smtp, 587, Socket::Starttls), true);
+        assert_eq!((grade.grade, grade.score), (Grade::B, 80));
+
+        assert!(GradingRules::from_yaml("penalties:\n  bogus: 1\n").is_err());
+        assert!(GradingRules::from_yaml("penalty:\n  starttls: 20\n").is_err());
+        assert!(GradingRules::from_yaml("thresholds:\n  e: 1\n").is_err());
+    }
+
+    #[test]
+    fn test_grade_entry() {
+        let db = ProviderDb::bundled();
+        let rules = GradingRules::default();
+        let provider = db.get_provider_by_domain("nauta.cu").unwrap();
+        let entry = crate::buildin::deltachat::entry(&db, "nauta.cu", provider);
+        let grade = rules.grade_entry(&entry, provider.opt.strict_tls);
+        assert_eq!(grade.servers.len(), 2);
+        assert_eq!(
+            grade.servers[0].issues,
+            [
+                Issue::Starttls,
+                Issue::NoStrictTls,
+                Issue::CleartextPassword
+            ]
+        );
+        assert_eq!((grade.grade, grade.score), (Grade::C, 55));
+    }
+
+    #[test]
+    fn test_same_server_same_grade() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../buildinlists");
+        let rules = GradingRules::default();
+        let penalty = rules.penalties[&Issue::CleartextPassword];
+        let mut scores = BTreeMap::new();
+        for source in Source::ALL {
+            let src = std::fs::read_to_string(dir.join(source.list_file())).unwrap();
+            for entry in read_list(source, &src).unwrap() {
+                if entry.domain != "gmail.com" {
+                    continue;
+                }
+                for server in entry.servers() {
+                    let grade = rules.grade_server(server, true);
+                    // Only lists stating the password method are penalized
+                    // for it; everything else grades the same.
+                    let cleartext = server
+                        .authentication
+                        .contains(&Authentication::PasswordCleartext);
+                    assert_eq!(grade.issues.contains(&Issue::CleartextPassword), cleartext);
+                    let score = grade.score + if cleartext { penalty } else { 0 };
+                    scores
+                        .entry((server.hostname.clone(), server.port))
+                        .or_insert_with(Vec::new)
+                        .push((source, score));
+                }
+            }
+        }
+        let smtp = &scores[&("smtp.gmail.com".to_string(), 465)];
+        assert!(smtp.len() >= 3, "{smtp:?}");
+        for sources in scores.values() {
+            assert!(
+                sources.iter().all(|(_, s)| *s == sources[0].1),
+                "{sources:?}"
+            );
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! Internationalized domain names.
+//!
+//! Lookups compare domains in their ASCII form after UTS-46 processing, so
+//! `Bücher.de`, `bücher.de.` and `xn--bcher-kva.de` all match the same
+//! `PROVIDER_DATA` pattern or list entry.
+
+/// ASCII form of a domain: lowercase, with A-labels for non-ASCII labels and
+/// without trailing dot. `None` if UTS-46 rejects the domain.
+pub fn to_ascii(domain: &str) -> Option<String> {
+    let domain = domain.strip_suffix('.').unwrap_or(domain);
+    idna::domain_to_ascii(domain).ok()
+}
+
+/// Unicode form of a domain, with U-labels for A-labels. Labels that are not
+/// valid A-labels are kept.
+pub fn to_unicode(domain: &str) -> String {
+    let domain = domain.strip_suffix('.').unwrap_or(domain);
+    idna::domain_to_unicode(domain).0
+}
+
+/// Form domains, MX hostnames and patterns are compared in: the ASCII form,
+/// or just the lowercased name if UTS-46 rejects it.
+///
+/// A leading `*.` of a wildcard pattern is kept.
+pub fn normalize(domain: &str) -> String {
+    if let Some(suffix) = domain.strip_prefix("*.") {
+        return format!("*.{}", normalize(suffix));
+    }
+    to_ascii(domain).unwrap_or_else(|| domain.strip_suffix('.').unwrap_or(domain).to_lowercase())
+}
+
+/// Unicode form of a domain if it differs from the ASCII one.
+pub fn unicode_if_different(domain: &str) -> Option<String> {
+    let unicode = to_unicode(domain);
+    (unicode != normalize(domain)).then_some(unicode)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize("Bücher.DE."), "xn--bcher-kva.de");
+        assert_eq!(normalize("XN--BCHER-KVA.de"), "xn--bcher-kva.de");
+        assert_eq!(normalize("*.Bücher.de"), "*.xn--bcher-kva.de");
+        assert_eq!(to_unicode("xn--bcher-kva.de"), "bücher.de");
+        assert_eq!(
+            unicode_if_different("xn--bcher-kva.de").as_deref(),
+            Some("bücher.de")
+        );
+        assert_eq!(unicode_if_different("gmail.com"), None);
+    }
+}
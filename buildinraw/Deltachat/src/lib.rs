@@ -0,0 +1,17 @@
+//! Delta Chat provider database tooling for the mailconfig built-in lists.
+
+pub mod buildin;
+pub mod config;
+pub mod consistency;
+pub mod email;
+pub mod export;
+pub mod grade;
+pub mod idn;
+pub mod matcher;
+pub mod mock;
+pub mod mx;
+pub mod output;
+pub mod provider;
+pub mod psl;
+pub mod schema;
+pub mod settings;
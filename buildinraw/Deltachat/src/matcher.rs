@@ -0,0 +1,442 @@
+//! Domain and MX matching over the built-in lists.
+//!
+//! This is the Rust counterpart of `buildin.py`: an entry matches a domain
+//! if its `domain` equals it, if the `domain_regular` or `mx_regular`
+//! expressions enabled for its list in `key.json` match the domain or one of
+//! its MX hostnames, or, like upstream `get_provider_by_domain`, if its domain
+//! is a `*.suffix` wildcard covering the domain.
+//!
+//! Regular expressions must match the whole name, as `buildin.py` requires
+//! with `re.match(...).group() == domain`. Names are compared in their ASCII
+//! form after UTS-46 processing, case-insensitively and without a trailing
+//! dot.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::buildin::Source;
+use crate::email::{AddressError, EmailAddress};
+use crate::idn;
+use crate::mx::MxSource;
+use crate::psl;
+use crate::schema::{self, Entry};
+
+/// Which regular expressions of a list's entries are used for matching.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Rules {
+    /// Match the domain against `domain_regular`.
+    pub domain_regex: bool,
+
+    /// Match MX hostnames against `mx_regular`.
+    pub mx_regex: bool,
+}
+
+/// One list of `key.json`, e.g. `{"domainre": ["domain_regular"]}`.
+#[derive(Debug, Default, Deserialize)]
+struct KeyRules {
+    #[serde(default)]
+    domainre: Vec<String>,
+    #[serde(default)]
+    mxre: Vec<String>,
+}
+
+impl Rules {
+    /// Rules of the bundled `buildinlists/key.json`.
+    pub fn for_source(source: Source) -> Self {
+        match source {
+            Source::Mailcore => Rules {
+                domain_regex: true,
+                mx_regex: true,
+            },
+            Source::FairEmail => Rules {
+                domain_regex: true,
+                mx_regex: false,
+            },
+            Source::DeltaChat | Source::Nodemailer | Source::Mailspring => Rules::default(),
+        }
+    }
+
+    /// Parses a `key.json` file.
+    ///
+    /// Lists missing from the file are not matched at all by `buildin.py`, so
+    /// they are missing from the result too.
+    pub fn from_key_json(src: &str) -> Result<HashMap<Source, Rules>> {
+        let keys: HashMap<Source, KeyRules> = serde_json::from_str(src)?;
+        Ok(keys
+            .into_iter()
+            .map(|(source, key)| {
+                let rules = Rules {
+                    domain_regex: key.domainre.iter().any(|f| f == "domain_regular"),
+                    mx_regex: key.mxre.iter().any(|f| f == "mx_regular"),
+                };
+                (source, rules)
+            })
+            .collect())
+    }
+}
+
+/// Why an entry matched.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MatchReason {
+    /// The entry's domain equals the looked up domain.
+    Exact,
+
+    /// The entry's domain is a `*.suffix` wildcard covering the domain.
+    Wildcard { pattern: String },
+
+    /// A `domain_regular` expression matches the domain.
+    DomainRegex { pattern: String },
+
+    /// A `mx_regular` expression matches one of the MX hostnames.
+    MxRegex { pattern: String, mx: String },
+
+    /// Nothing matched the domain itself, but the entry matches the parent
+    /// domain `domain` for `reason`.
+    ParentDomain {
+        domain: String,
+        reason: Box<MatchReason>,
+    },
+}
+
+/// Entry matching a lookup.
+#[derive(Debug, Clone, Serialize)]
+pub struct Match<'a> {
+    /// Why the entry matched. The first of exact, wildcard, domain regex
+    /// and MX regex that applies is reported.
+    pub reason: MatchReason,
+
+    /// The matching entry.
+    pub entry: &'a Entry,
+}
+
+#[derive(Debug)]
+struct List {
+    source: Source,
+    rules: Rules,
+    entries: Vec<Entry>,
+
+    /// Compiled `domain_regular` and `mx_regular` of each entry, empty if the
+    /// rules do not use them.
+    regexes: Vec<(Vec<Regex>, Vec<Regex>)>,
+}
+
+/// Matches domains against every loaded list.
+#[derive(Debug, Default)]
+pub struct Matcher {
+    lists: Vec<List>,
+}
+
+/// Compiles a pattern so that it only matches whole names.
+fn full_match(pattern: &str) -> Result<Regex> {
+    RegexBuilder::new(&format!("^(?:{pattern})$"))
+        .case_insensitive(true)
+        .build()
+        .with_context(|| format!("invalid regular expression {pattern:?}"))
+}
+
+fn normalize(name: &str) -> String {
+    idn::normalize(name.trim_end_matches('.'))
+}
+
+impl Matcher {
+    /// Creates a matcher without lists.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a list, matched with the given rules.
+    pub fn add_list(&mut self, source: Source, rules: Rules, entries: Vec<Entry>) -> Result<()> {
+        let compile = |enabled: bool, patterns: &[String]| -> Result<Vec<Regex>> {
+            if !enabled {
+                return Ok(Vec::new());
+            }
+            patterns.iter().map(|p| full_match(p)).collect()
+        };
+        let regexes = entries
+            .iter()
+            .map(|entry| {
+                Ok((
+                    compile(rules.domain_regex, &entry.domain_regular)?,
+                    compile(rules.mx_regex, &entry.mx_regular)?,
+                ))
+            })
+            .collect::<Result<_>>()
+            .with_context(|| format!("in {} list", source.key()))?;
+        self.lists.push(List {
+            source,
+            rules,
+            entries,
+            regexes,
+        });
+        Ok(())
+    }
+
+    /// Loads the lists of a `buildinlists` directory.
+    ///
+    /// If the directory has a `key.json`, only the lists it names are loaded,
+    /// with its rules; otherwise all lists are loaded with
+    /// [`Rules::for_source`].
+    pub fn load_dir(dir: &Path) -> Result<Self> {
+        let key_json = dir.join("key.json");
+        let rules = if key_json.exists() {
+            let src = std::fs::read_to_string(&key_json)
+                .with_context(|| format!("cannot read {}", key_json.display()))?;
+            Rules::from_key_json(&src)
+                .with_context(|| format!("cannot parse {}", key_json.display()))?
+        } else {
+            Source::ALL
+                .into_iter()
+                .map(|source| (source, Rules::for_source(source)))
+                .collect()
+        };
+
+        let mut matcher = Self::new();
+        for source in Source::ALL {
+            let Some(rules) = rules.get(&source) else {
+                continue;
+            };
+            let path = dir.join(source.list_file());
+            let src = std::fs::read_to_string(&path)
+                .with_context(|| format!("cannot read {}", path.display()))?;
+            let entries = schema::read_list(source, &src)
+                .with_context(|| format!("cannot parse {}", path.display()))?;
+            matcher.add_list(source, *rules, entries)?;
+        }
+        Ok(matcher)
+    }
+
+    /// Sources of the loaded lists, in load order.
+    pub fn sources(&self) -> impl Iterator<Item = Source> + '_ {
+        self.lists.iter().map(|list| list.source)
+    }
+
+    /// Rules a loaded list is matched with.
+    pub fn rules(&self, source: Source) -> Option<Rules> {
+        self.lists
+            .iter()
+            .find(|list| list.source == source)
+            .map(|list| list.rules)
+    }
+
+    /// Like [`lookup`](Matcher::lookup), with the MX hostnames of `domain`
+    /// taken from `mx`. A failed MX lookup matches as if there were no MX.
+    pub fn lookup_with_mx(&self, domain: &str, mx: &dyn MxSource) -> Vec<Match<'_>> {
+        let records = mx.mx_lookup(domain).unwrap_or_default();
+        let hosts: Vec<&str> = records.iter().map(|r| r.exchange.as_str()).collect();
+        self.lookup(domain, &hosts)
+    }
+
+    /// Like [`lookup_with_mx`](Matcher::lookup_with_mx), for the domain of an
+    /// email address.
+    pub fn lookup_addr(
+        &self,
+        addr: &str,
+        mx: &dyn MxSource,
+    ) -> Result<Vec<Match<'_>>, AddressError> {
+        let addr = EmailAddress::new(addr)?;
+        Ok(self.lookup_with_mx(&addr.domain, mx))
+    }
+
+    /// Finds all entries of all lists matching `domain` or one of the MX
+    /// hostnames `mx`, in list and entry order.
+    pub fn lookup(&self, domain: &str, mx: &[&str]) -> Vec<Match<'_>> {
+        let domain = normalize(domain);
+        let mx: Vec<String> = mx.iter().map(|m| normalize(m)).collect();
+        let mut matches = Vec::new();
+        for list in &self.lists {
+            for (entry, (domain_res, mx_res)) in list.entries.iter().zip(&list.regexes) {
+                if let Some(reason) = reason(entry, domain_res, mx_res, &domain, &mx) {
+                    matches.push(Match { reason, entry });
+                }
+            }
+        }
+        matches
+    }
+
+    /// Like [`lookup`](Matcher::lookup), falling back to the parent domains
+    /// of `domain` up to its registered domain if nothing matches. Only the
+    /// nearest parent with matches is reported.
+    pub fn lookup_with_parents(&self, domain: &str, mx: &[&str]) -> Vec<Match<'_>> {
+        let matches = self.lookup(domain, mx);
+        if !matches.is_empty() {
+            return matches;
+        }
+        for parent in psl::parent_domains(domain) {
+            let matches = self.lookup(&parent, mx);
+            if !matches.is_empty() {
+                return matches
+                    .into_iter()
+                    .map(|m| Match {
+                        reason: MatchReason::ParentDomain {
+                            domain: parent.clone(),
+                            reason: Box::new(m.reason),
+                        },
+                        entry: m.entry,
+                    })
+                    .collect();
+            }
+        }
+        Vec::new()
+    }
+}
+
+fn reason(
+    entry: &Entry,
+    domain_res: &[Regex],
+    mx_res: &[Regex],
+    domain: &str,
+    mx: &[String],
+) -> Option<MatchReason> {
+    let entry_domain = normalize(&entry.domain);
+    if entry_domain == domain {
+        return Some(MatchReason::Exact);
+    }
+    if let Some(suffix) = entry_domain.strip_prefix('*') {
+        // For example, `suffix` is ".hermes.radio" for "*.hermes.radio" pattern.
+        if domain.ends_with(suffix) {
+            return Some(MatchReason::Wildcard {
+                pattern: entry.domain.clone(),
+            });
+        }
+    }
+    if let Some(re) = domain_res.iter().find(|re| re.is_match(domain)) {
+        return Some(MatchReason::DomainRegex {
+            pattern: pattern(re),
+        });
+    }
+    for mx in mx {
+        if let Some(re) = mx_res.iter().find(|re| re.is_match(mx)) {
+            return Some(MatchReason::MxRegex {
+                pattern: pattern(re),
+                mx: mx.clone(),
+            });
+        }
+    }
+    None
+}
+
+/// The pattern as written in the list, without the anchors of [`full_match`].
+fn pattern(re: &Regex) -> String {
+    let s = re.as_str();
+    s["^(?:".len()..s.len() - ")$".len()].to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mx::StaticMx;
+
+    fn bundled() -> Matcher {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../buildinlists");
+        Matcher::load_dir(&dir).unwrap()
+    }
+
+    #[test]
+    fn test_exact_and_wildcard() {
+        let matcher = bundled();
+        let matches = matcher.lookup("GMail.com.", &[]);
+        let sources: Vec<Source> = matches.iter().map(|m| m.entry.source).collect();
+        assert!(sources.contains(&Source::DeltaChat));
+        assert!(sources.contains(&Source::Nodemailer));
+        assert!(matches
+            .iter()
+            .any(|m| m.entry.source == Source::FairEmail && m.reason == MatchReason::Exact));
+
+        let matches = matcher.lookup_with_parents("imap.mail.gmail.com", &[]);
+        assert!(!matches.is_empty());
+        assert!(matches.iter().any(|m| m.reason
+            == MatchReason::ParentDomain {
+                domain: "gmail.com".to_string(),
+                reason: Box::new(MatchReason::Exact),
+            }));
+        assert_eq!(
+            matcher.lookup_with_parents("GMail.com", &[]).len(),
+            matcher.lookup("gmail.com", &[]).len()
+        );
+
+        let matches = matcher.lookup("team.hermes.radio", &[]);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(
+            matches[0].reason,
+            MatchReason::Wildcard {
+                pattern: "*.hermes.radio".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_regex() {
+        let matcher = bundled();
+        let matches = matcher.lookup("hotmail.de", &[]);
+        assert!(matches.iter().any(|m| m.entry.source == Source::FairEmail
+            && matches!(m.reason, MatchReason::DomainRegex { .. })));
+
+        let mx: StaticMx = [("pobox-customer.test", 10, "mx-2.pobox.com.")]
+            .into_iter()
+            .collect();
+        let matches = matcher.lookup_with_mx("pobox-customer.test", &mx);
+        assert_eq!(matches.len(), 1);
+        assert_eq!(
+            matches[0].reason,
+            MatchReason::MxRegex {
+                pattern: r"mx-2\.pobox\.com".to_string(),
+                mx: "mx-2.pobox.com".to_string(),
+            }
+        );
+
+        let matches = matcher
+            .lookup_addr("carol@pobox-customer.test", &mx)
+            .unwrap();
+        assert_eq!(matches.len(), 1);
+        assert!(matcher.lookup_addr("pobox-customer.test", &mx).is_err());
+
+        // Only whole names match.
+        assert!(matcher
+            .lookup("pobox-customer.test", &["mx-2.pobox.com.evil"])
+            .is_empty());
+    }
+
+    #[test]
+    fn test_idn() {
+        let mut matcher = Matcher::new();
+        let mut entry = Entry::new(Source::Nodemailer, "xn--bcher-kva.de");
+        entry.mx_regular = vec![r"mx\.xn--bcher-kva\.de".to_string()];
+        let rules = Rules {
+            domain_regex: false,
+            mx_regex: true,
+        };
+        matcher
+            .add_list(Source::Nodemailer, rules, vec![entry])
+            .unwrap();
+        assert_eq!(
+            matcher.lookup("Bücher.de", &[])[0].reason,
+            MatchReason::Exact
+        );
+        assert!(matches!(
+            matcher.lookup("example.org", &["MX.bücher.de."])[0].reason,
+            MatchReason::MxRegex { .. }
+        ));
+    }
+
+    #[test]
+    fn test_key_json_rules() {
+        let rules =
+            Rules::from_key_json(r#"{"mailcore": {"mxre": ["mx_regular"]}, "DeltaChat": {}}"#)
+                .unwrap();
+        assert_eq!(rules.len(), 2);
+        assert!(rules[&Source::Mailcore].mx_regex);
+        assert!(!rules[&Source::Mailcore].domain_regex);
+        assert_eq!(rules[&Source::DeltaChat], Rules::default());
+
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../buildinlists");
+        let src = std::fs::read_to_string(dir.join("key.json")).unwrap();
+        for (source, rules) in Rules::from_key_json(&src).unwrap() {
+            assert_eq!(rules, Rules::for_source(source), "{source:?}");
+        }
+    }
+}
@@ -0,0 +1,462 @@
+//! Stand-in for the autoconfiguration services mail clients probe.
+//!
+//! [`Responder`] answers the requests `autoconfig.py` and `autodiscover.py`
+//! send, from the loaded provider database instead of the real providers:
+//!
+//! - `/mail/config-v1.1.xml` on `autoconfig.<domain>`
+//! - `/.well-known/autoconfig/mail/config-v1.1.xml` on `<domain>`
+//! - `/autodiscover/autodiscover.xml` on `<domain>` or `autodiscover.<domain>`,
+//!   with GET or POST
+//! - the ISPDB `/v1.1/<domain>`
+//!
+//! The domain is taken from the email address of the request if it has one,
+//! else from the host mapping, else from the `Host` header. The HTTP server
+//! itself is the `mockserver` binary.
+//!
+//! [`Faults`] make the responder misbehave like the attack scenarios of the
+//! paper, e.g. a domain collision redirecting to a foreign host or a
+//! downgrade to plaintext servers, so scanners and clients can be checked
+//! for detecting them.
+
+use std::borrow::Cow;
+use std::collections::{BTreeSet, HashMap};
+use std::time::Duration;
+
+use clap::ValueEnum;
+
+use crate::email::EmailAddress;
+use crate::export::{autodiscover, thunderbird};
+use crate::provider::{Protocol, Provider, ProviderDb, Socket};
+
+/// HTTP request, as far as the responder cares.
+#[derive(Debug, Clone, Copy)]
+pub struct Request<'a> {
+    pub method: &'a str,
+
+    /// Path including the query string.
+    pub url: &'a str,
+
+    /// `Host` header, possibly with a port.
+    pub host: Option<&'a str>,
+    pub body: &'a [u8],
+}
+
+/// HTTP response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: u16,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+
+    /// Time to wait before sending the response.
+    pub delay: Duration,
+}
+
+impl Response {
+    fn xml(body: String) -> Self {
+        Self {
+            status: 200,
+            headers: vec![("Content-Type".to_string(), "text/xml".to_string())],
+            body: body.into_bytes(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    fn redirect(location: String) -> Self {
+        Self {
+            status: 301,
+            headers: vec![("Location".to_string(), location)],
+            body: Vec::new(),
+            delay: Duration::ZERO,
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Self {
+        Self {
+            status,
+            headers: vec![("Content-Type".to_string(), "text/plain".to_string())],
+            body: format!("{message}\n").into_bytes(),
+            delay: Duration::ZERO,
+        }
+    }
+}
+
+const AUTOCONFIG_PATHS: [&str; 2] = [
+    "/mail/config-v1.1.xml",
+    "/.well-known/autoconfig/mail/config-v1.1.xml",
+];
+
+const AUTODISCOVER_PATH: &str = "/autodiscover/autodiscover.xml";
+
+/// Document a request asks for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Document {
+    Autoconfig,
+    Autodiscover,
+}
+
+/// Misbehaviour of the mock server.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, ValueEnum)]
+pub enum Fault {
+    /// Redirect every request to HTTPS on a foreign host.
+    ForeignRedirect,
+
+    /// Answer Autodiscover with a `redirectUrl` to the requested URL.
+    RedirectUrlLoop,
+
+    /// Answer Autodiscover with a `redirectAddr` to the requested address.
+    RedirectAddrLoop,
+
+    /// Serve documents as `text/html`.
+    WrongContentType,
+
+    /// Cut documents off halfway.
+    MalformedXml,
+
+    /// Offer every server without TLS, on the standard plaintext port.
+    PlaintextOnly,
+
+    /// Serve HTTPS with a self-signed certificate. This is up to the server;
+    /// the responder ignores it.
+    SelfSigned,
+
+    /// Wait before every response.
+    Slow,
+}
+
+/// Faults a responder injects.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Faults {
+    pub enabled: BTreeSet<Fault>,
+
+    /// Host [`Fault::ForeignRedirect`] redirects to.
+    pub foreign_host: String,
+
+    /// Wait of [`Fault::Slow`].
+    pub delay: Duration,
+}
+
+impl Default for Faults {
+    fn default() -> Self {
+        Self {
+            enabled: BTreeSet::new(),
+            foreign_host: "attacker.example".to_string(),
+            delay: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Answers autoconfiguration requests from a provider database.
+#[derive(Debug)]
+pub struct Responder {
+    db: ProviderDb,
+    hosts: HashMap<String, String>,
+    faults: Faults,
+}
+
+impl Responder {
+    pub fn new(db: ProviderDb) -> Self {
+        Self {
+            db,
+            hosts: HashMap::new(),
+            faults: Faults::default(),
+        }
+    }
+
+    /// Injects `faults` into all following responses.
+    pub fn set_faults(&mut self, faults: Faults) {
+        self.faults = faults;
+    }
+
+    fn has(&self, fault: Fault) -> bool {
+        self.faults.enabled.contains(&fault)
+    }
+
+    /// Answers requests for `host` as if they were for `domain`. The host
+    /// may include a port, e.g. `127.0.0.1:8080`, to only map that port.
+    pub fn map_host(&mut self, host: &str, domain: &str) {
+        self.hosts
+            .insert(host.to_lowercase(), domain.to_lowercase());
+    }
+
+    /// Answers a request.
+    pub fn respond(&self, request: &Request) -> Response {
+        let mut response = if self.has(Fault::ForeignRedirect) {
+            Response::redirect(format!(
+                "https://{}{}",
+                self.faults.foreign_host, request.url
+            ))
+        } else {
+            self.document(request)
+        };
+        if response.status == 200 {
+            if self.has(Fault::WrongContentType) {
+                response.headers = vec![("Content-Type".to_string(), "text/html".to_string())];
+            }
+            if self.has(Fault::MalformedXml) {
+                response.body.truncate(response.body.len() / 2);
+            }
+        }
+        if self.has(Fault::Slow) {
+            response.delay = self.faults.delay;
+        }
+        response
+    }
+
+    /// Renders the requested document.
+    fn document(&self, request: &Request) -> Response {
+        let (path, query) = request.url.split_once('?').unwrap_or((request.url, ""));
+        let mut email = query_param(query, "emailaddress");
+
+        let document = if path.starts_with("/v1.1/") || AUTOCONFIG_PATHS.contains(&path) {
+            Document::Autoconfig
+        } else if path.eq_ignore_ascii_case(AUTODISCOVER_PATH) {
+            if !matches!(request.method, "GET" | "POST") {
+                return Response::error(405, "method not allowed");
+            }
+            email = email.or_else(|| request_email(request.body));
+            Document::Autodiscover
+        } else {
+            return Response::error(404, "not found");
+        };
+
+        let email = match email.as_deref().map(EmailAddress::new).transpose() {
+            Ok(email) => email,
+            Err(err) => return Response::error(400, &err.to_string()),
+        };
+        let domain = match path.strip_prefix("/v1.1/") {
+            Some(domain) => Some(domain.to_lowercase()),
+            None => self.domain(request, email.as_ref()),
+        };
+        let email = email.map(|email| email.to_string());
+
+        if document == Document::Autodiscover {
+            match &email {
+                Some(email) if self.has(Fault::RedirectAddrLoop) => {
+                    return Response::xml(autodiscover::redirect_addr(email));
+                }
+                _ if self.has(Fault::RedirectUrlLoop) => {
+                    let host = request.host.unwrap_or("localhost");
+                    let url = format!("https://{host}{}", request.url);
+                    return Response::xml(autodiscover::redirect_url(&url));
+                }
+                _ => {}
+            }
+        }
+
+        let Some(provider) = domain.and_then(|d| self.db.get_provider_by_domain(&d)) else {
+            return Response::error(404, "unknown domain");
+        };
+        let domains = self.db.domains_of(provider);
+        let provider = if self.has(Fault::PlaintextOnly) {
+            Cow::Owned(plaintext(provider))
+        } else {
+            Cow::Borrowed(provider)
+        };
+        Response::xml(match document {
+            Document::Autoconfig => thunderbird::config_v11(&provider, domains),
+            Document::Autodiscover => autodiscover::settings(&provider, email.as_deref()),
+        })
+    }
+
+    /// Domain a request is for.
+    fn domain(&self, request: &Request, email: Option<&EmailAddress>) -> Option<String> {
+        if let Some(email) = email {
+            return Some(email.domain.clone());
+        }
+        let host = request.host?.to_lowercase();
+        let name = host
+            .rsplit_once(':')
+            .map_or(host.as_str(), |(name, _)| name);
+        if let Some(domain) = self.hosts.get(&host).or_else(|| self.hosts.get(name)) {
+            return Some(domain.clone());
+        }
+        let domain = ["autoconfig.", "autodiscover."]
+            .iter()
+            .find_map(|prefix| name.strip_prefix(prefix))
+            .unwrap_or(name);
+        Some(domain.to_string())
+    }
+}
+
+/// The provider with every server downgraded to plaintext.
+fn plaintext(provider: &Provider) -> Provider {
+    let mut provider = provider.clone();
+    for server in &mut provider.server {
+        server.socket = Socket::Plain;
+        server.port = match server.protocol {
+            Protocol::Imap => 143,
+            Protocol::Pop3 => 110,
+            Protocol::Smtp => 587,
+            Protocol::Jmap | Protocol::Ews | Protocol::ActiveSync => 80,
+        };
+    }
+    provider
+}
+
+/// `EMailAddress` of an Autodiscover POX request body.
+fn request_email(body: &[u8]) -> Option<String> {
+    let body = std::str::from_utf8(body).ok()?;
+    let doc = roxmltree::Document::parse(body).ok()?;
+    let email = doc
+        .descendants()
+        .find(|n| n.tag_name().name() == "EMailAddress")?;
+    Some(email.text()?.trim().to_string())
+}
+
+/// Percent-decoded value of a query parameter, compared case-insensitively.
+fn query_param(query: &str, name: &str) -> Option<String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| key.eq_ignore_ascii_case(name))
+        .map(|(_, value)| percent_decode(value))
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|h| std::str::from_utf8(h).ok())
+            .and_then(|h| u8::from_str_radix(h, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (b'+', _) => {
+                out.push(b' ');
+                i += 1;
+            }
+            (byte, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get<'a>(host: &'a str, url: &'a str) -> Request<'a> {
+        Request {
+            method: "GET",
+            url,
+            host: Some(host),
+            body: b"",
+        }
+    }
+
+    #[test]
+    fn test_autoconfig() {
+        let mut responder = Responder::new(ProviderDb::bundled());
+        let response = responder.respond(&get(
+            "autoconfig.gmail.com",
+            "/mail/config-v1.1.xml?emailaddress=alice%40gmail.com",
+        ));
+        assert_eq!(response.status, 200);
+        assert!(String::from_utf8(response.body)
+            .unwrap()
+            .contains("<hostname>imap.gmail.com</hostname>"));
+
+        let response = responder.respond(&get(
+            "gmail.com",
+            "/.well-known/autoconfig/mail/config-v1.1.xml",
+        ));
+        assert_eq!(response.status, 200);
+
+        let response = responder.respond(&get("127.0.0.1:8080", "/v1.1/posteo.de"));
+        assert!(String::from_utf8(response.body)
+            .unwrap()
+            .contains("<emailProvider id=\"posteo\">"));
+
+        let response = responder.respond(&get("127.0.0.1:8080", "/mail/config-v1.1.xml"));
+        assert_eq!(response.status, 404);
+        responder.map_host("127.0.0.1:8080", "posteo.de");
+        let response = responder.respond(&get("127.0.0.1:8080", "/mail/config-v1.1.xml"));
+        assert_eq!(response.status, 200);
+    }
+
+    #[test]
+    fn test_autodiscover() {
+        let responder = Responder::new(ProviderDb::bundled());
+        let body = br#"<?xml version="1.0" encoding="utf-8"?>
+<Autodiscover xmlns="http://schemas.microsoft.com/exchange/autodiscover/outlook/requestschema/2006">
+  <Request>
+    <EMailAddress>bob@nauta.cu</EMailAddress>
+    <AcceptableResponseSchema>http://schemas.microsoft.com/exchange/autodiscover/outlook/responseschema/2006a</AcceptableResponseSchema>
+  </Request>
+</Autodiscover>"#;
+        let response = responder.respond(&Request {
+            method: "POST",
+            url: "/autodiscover/autodiscover.xml",
+            host: Some("127.0.0.1"),
+            body,
+        });
+        assert_eq!(response.status, 200);
+        let body = String::from_utf8(response.body).unwrap();
+        assert!(body.contains("<Server>imap.nauta.cu</Server>"));
+        assert!(body.contains("<LoginName>bob@nauta.cu</LoginName>"));
+
+        let response = responder.respond(&get(
+            "autodiscover.nauta.cu",
+            "/autodiscover/autodiscover.xml?emailaddress=bob",
+        ));
+        assert_eq!(response.status, 400);
+
+        let response = responder.respond(&get(
+            "autodiscover.unknown.example",
+            "/autodiscover/autodiscover.xml",
+        ));
+        assert_eq!(response.status, 404);
+    }
+
+    #[test]
+    fn test_faults() {
+        let mut responder = Responder::new(ProviderDb::bundled());
+        let mut faults = Faults {
+            enabled: BTreeSet::from([Fault::ForeignRedirect, Fault::Slow]),
+            ..Faults::default()
+        };
+        responder.set_faults(faults.clone());
+        let response = responder.respond(&get("autoconfig.gmail.com", "/mail/config-v1.1.xml"));
+        assert_eq!(response.status, 301);
+        assert_eq!(
+            response.headers,
+            [(
+                "Location".to_string(),
+                "https://attacker.example/mail/config-v1.1.xml".to_string()
+            )]
+        );
+        assert_eq!(response.delay, faults.delay);
+
+        faults.enabled = BTreeSet::from([Fault::PlaintextOnly, Fault::MalformedXml]);
+        responder.set_faults(faults.clone());
+        let response = responder.respond(&get("autoconfig.gmail.com", "/mail/config-v1.1.xml"));
+        let body = String::from_utf8(response.body).unwrap();
+        assert!(body.contains("<domain>gmail.com</domain>"));
+        assert!(body.contains("<socketType>plain</socketType>"));
+        assert!(roxmltree::Document::parse(&body).is_err());
+
+        faults.enabled = BTreeSet::from([Fault::RedirectUrlLoop, Fault::WrongContentType]);
+        responder.set_faults(faults);
+        let response = responder.respond(&get(
+            "autodiscover.gmail.com",
+            "/autodiscover/autodiscover.xml",
+        ));
+        assert_eq!(
+            response.headers,
+            [("Content-Type".to_string(), "text/html".to_string())]
+        );
+        let body = String::from_utf8(response.body).unwrap();
+        assert!(body.contains(
+            "<RedirectUrl>https://autodiscover.gmail.com/autodiscover/autodiscover.xml</RedirectUrl>"
+        ));
+    }
+}
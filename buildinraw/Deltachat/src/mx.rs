@@ -0,0 +1,313 @@
+//! Offline MX record sources.
+//!
+//! Upstream resolves MX records with a live resolver. Here lookups go through
+//! the [`MxSource`] trait instead, so provider lookup by MX works without
+//! network access: from a static map, a DNS zone file or a file of recorded
+//! resolver responses.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anyhow::{bail, Context as _, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::idn;
+
+/// MX resource record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MxRecord {
+    /// Preference, lower is tried first.
+    #[serde(rename = "priority")]
+    pub preference: u16,
+
+    /// Mail exchange hostname, lowercase ASCII and without trailing dot.
+    #[serde(rename = "hostname")]
+    pub exchange: String,
+}
+
+impl MxRecord {
+    pub fn new(preference: u16, exchange: &str) -> Self {
+        Self {
+            preference,
+            exchange: normalize(exchange),
+        }
+    }
+}
+
+/// Source of MX records.
+pub trait MxSource {
+    /// Returns the MX records of `domain` ordered by preference, empty if it
+    /// has none.
+    ///
+    /// An error means the lookup itself failed, e.g. a recorded timeout.
+    fn mx_lookup(&self, domain: &str) -> Result<Vec<MxRecord>>;
+}
+
+/// Converts a DNS name to its lowercase ASCII form without trailing dot.
+fn normalize(name: &str) -> String {
+    idn::normalize(name.trim_end_matches('.'))
+}
+
+fn sorted(records: &[MxRecord]) -> Vec<MxRecord> {
+    let mut records = records.to_vec();
+    records.sort_by_key(|r| r.preference);
+    records
+}
+
+/// MX records kept in memory.
+#[derive(Debug, Clone, Default)]
+pub struct StaticMx {
+    records: HashMap<String, Vec<MxRecord>>,
+}
+
+impl StaticMx {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an MX record for `domain`.
+    pub fn insert(&mut self, domain: &str, preference: u16, exchange: &str) {
+        self.records
+            .entry(normalize(domain))
+            .or_default()
+            .push(MxRecord::new(preference, exchange));
+    }
+}
+
+impl<'a> FromIterator<(&'a str, u16, &'a str)> for StaticMx {
+    fn from_iter<I: IntoIterator<Item = (&'a str, u16, &'a str)>>(iter: I) -> Self {
+        let mut mx = Self::new();
+        for (domain, preference, exchange) in iter {
+            mx.insert(domain, preference, exchange);
+        }
+        mx
+    }
+}
+
+impl MxSource for StaticMx {
+    fn mx_lookup(&self, domain: &str) -> Result<Vec<MxRecord>> {
+        Ok(self
+            .records
+            .get(&normalize(domain))
+            .map(|r| sorted(r))
+            .unwrap_or_default())
+    }
+}
+
+/// MX records of a DNS zone file in master file format.
+///
+/// `$ORIGIN`, `$TTL`, `@`, relative names, owners inherited from the previous
+/// record and optional TTL and class fields are understood. Records other
+/// than MX are ignored; `$INCLUDE` and multi-line records are not supported.
+#[derive(Debug, Clone, Default)]
+pub struct ZoneFileMx {
+    records: StaticMx,
+}
+
+impl ZoneFileMx {
+    /// Parses a zone file. `origin` is the initial `$ORIGIN`, if any.
+    pub fn parse(src: &str, origin: Option<&str>) -> Result<Self> {
+        let mut origin = origin.map(normalize);
+        let mut owner: Option<String> = None;
+        let mut records = StaticMx::new();
+
+        let absolute = |name: &str, origin: &Option<String>| -> Result<String> {
+            if name == "@" {
+                return origin.clone().context("@ without $ORIGIN");
+            }
+            if name.ends_with('.') {
+                return Ok(normalize(name));
+            }
+            match origin {
+                Some(origin) if origin.is_empty() => Ok(normalize(name)),
+                Some(origin) => Ok(format!("{}.{origin}", normalize(name))),
+                None => bail!("relative name {name:?} without $ORIGIN"),
+            }
+        };
+
+        for (i, line) in src.lines().enumerate() {
+            let line = line.split(';').next().unwrap_or_default();
+            let mut fields = line.split_whitespace().peekable();
+            let Some(first) = fields.peek().copied() else {
+                continue;
+            };
+            let lineno = i + 1;
+            if line.contains(['(', ')']) {
+                bail!("line {lineno}: multi-line records are not supported");
+            }
+            match first.to_ascii_uppercase().as_str() {
+                "$ORIGIN" => {
+                    let name = fields
+                        .nth(1)
+                        .with_context(|| format!("line {lineno}: missing origin"))?;
+                    origin = Some(normalize(name));
+                    continue;
+                }
+                "$TTL" => continue,
+                directive if directive.starts_with('$') => {
+                    bail!("line {lineno}: unsupported directive {first}")
+                }
+                _ => {}
+            }
+
+            if !line.starts_with(char::is_whitespace) {
+                let name = fields.next().unwrap_or_default();
+                owner = Some(absolute(name, &origin).with_context(|| format!("line {lineno}"))?);
+            }
+            let Some(owner) = &owner else {
+                bail!("line {lineno}: record without owner");
+            };
+
+            // Optional TTL and class, in either order.
+            while let Some(field) = fields.peek() {
+                let is_class = ["IN", "CH", "HS"]
+                    .iter()
+                    .any(|c| field.eq_ignore_ascii_case(c));
+                if is_class || field.chars().all(|c| c.is_ascii_digit()) {
+                    fields.next();
+                } else {
+                    break;
+                }
+            }
+            let Some(rtype) = fields.next() else {
+                bail!("line {lineno}: missing record type");
+            };
+            if !rtype.eq_ignore_ascii_case("MX") {
+                continue;
+            }
+            let (Some(preference), Some(exchange)) = (fields.next(), fields.next()) else {
+                bail!("line {lineno}: incomplete MX record");
+            };
+            let preference = preference
+                .parse()
+                .with_context(|| format!("line {lineno}: invalid MX preference {preference:?}"))?;
+            let exchange = absolute(exchange, &origin).with_context(|| format!("line {lineno}"))?;
+            records.insert(owner, preference, &exchange);
+        }
+        Ok(Self { records })
+    }
+
+    /// Reads and parses a zone file.
+    pub fn from_file(path: &Path, origin: Option<&str>) -> Result<Self> {
+        let src = std::fs::read_to_string(path)
+            .with_context(|| format!("cannot read {}", path.display()))?;
+        Self::parse(&src, origin).with_context(|| format!("cannot parse {}", path.display()))
+    }
+}
+
+impl MxSource for ZoneFileMx {
+    fn mx_lookup(&self, domain: &str) -> Result<Vec<MxRecord>> {
+        self.records.mx_lookup(domain)
+    }
+}
+
+/// Recorded resolver response for one domain.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum Response {
+    /// Records, as returned by `resolve_mx` in `srv.py`.
+    Records(Vec<MxRecord>),
+
+    /// Resolver error, e.g. `"timeout"`.
+    Error(String),
+}
+
+/// MX responses recorded from a live resolver.
+///
+/// The file is a JSON object mapping domains to the `resolve_mx` output of
+/// `srv.py`, or to an error string for lookups that failed:
+///
+/// ```json
+/// {"gmail.com": [{"hostname": "gmail-smtp-in.l.google.com", "priority": 5}],
+///  "broken.example": "timeout"}
+/// ```
+///
+/// Domains missing from the file have no MX records.
+#[derive(Debug, Clone, Default)]
+pub struct RecordedMx {
+    responses: HashMap<String, Response>,
+}
+
+impl RecordedMx {
+    /// Parses recorded responses.
+    pub fn parse(src: &str) -> Result<Self> {
+        let responses: HashMap<String, Response> = serde_json::from_str(src)?;
+        Ok(Self {
+            responses: responses
+                .into_iter()
+                .map(|(domain, response)| (normalize(&domain), response))
+                .collect(),
+        })
+    }
+
+    /// Reads and parses a file of recorded responses.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let src = std::fs::read_to_string(path)
+            .with_context(|| format!("cannot read {}", path.display()))?;
+        Self::parse(&src).with_context(|| format!("cannot parse {}", path.display()))
+    }
+}
+
+impl MxSource for RecordedMx {
+    fn mx_lookup(&self, domain: &str) -> Result<Vec<MxRecord>> {
+        match self.responses.get(&normalize(domain)) {
+            Some(Response::Records(records)) => Ok(sorted(
+                &records
+                    .iter()
+                    .map(|r| MxRecord::new(r.preference, &r.exchange))
+                    .collect::<Vec<_>>(),
+            )),
+            Some(Response::Error(err)) => bail!("MX lookup for {domain:?} failed: {err}"),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zone_file() {
+        let zone = ZoneFileMx::parse(
+            "$ORIGIN example.org.
+$TTL 3600
+@       IN MX 20 mx2
+        IN MX 10 mx1.example.org. ; primary
+        IN A  192.0.2.1
+sub 300 IN MX 5 ASPMX.L.GOOGLE.COM.
+",
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            zone.mx_lookup("Example.org.").unwrap(),
+            vec![
+                MxRecord::new(10, "mx1.example.org"),
+                MxRecord::new(20, "mx2.example.org")
+            ]
+        );
+        assert_eq!(
+            zone.mx_lookup("sub.example.org").unwrap(),
+            vec![MxRecord::new(5, "aspmx.l.google.com")]
+        );
+        assert!(zone.mx_lookup("other.example.org").unwrap().is_empty());
+
+        assert!(ZoneFileMx::parse("example.org. MX 10 mx", None).is_err());
+        assert!(ZoneFileMx::parse("$INCLUDE other.zone", None).is_err());
+    }
+
+    #[test]
+    fn test_recorded() {
+        let recorded = RecordedMx::parse(
+            r#"{"gmail.com": [{"hostname": "alt1.gmail-smtp-in.l.google.com", "priority": 10},
+                             {"hostname": "gmail-smtp-in.l.google.com.", "priority": 5}],
+                "broken.example": "timeout"}"#,
+        )
+        .unwrap();
+        let records = recorded.mx_lookup("gmail.com").unwrap();
+        assert_eq!(records[0].exchange, "gmail-smtp-in.l.google.com");
+        assert!(recorded.mx_lookup("broken.example").is_err());
+        assert!(recorded.mx_lookup("unknown.example").unwrap().is_empty());
+    }
+}
@@ -0,0 +1,44 @@
+//! Output files of the binaries.
+
+use std::io::Write;
+use std::path::Path;
+
+use anyhow::{Context as _, Result};
+use serde::Serialize;
+
+/// Opens the file to write to, or stdout for `-`.
+pub fn open_output(path: &Path) -> Result<Box<dyn Write>> {
+    if path.as_os_str() == "-" {
+        return Ok(Box::new(std::io::stdout().lock()));
+    }
+    let file =
+        std::fs::File::create(path).with_context(|| format!("cannot create {}", path.display()))?;
+    Ok(Box::new(std::io::BufWriter::new(file)))
+}
+
+/// Writes a value as pretty-printed JSON followed by a newline.
+pub fn write_json<T: Serialize + ?Sized>(out: &mut dyn Write, value: &T) -> Result<()> {
+    serde_json::to_writer_pretty(&mut *out, value)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_output() {
+        let path = std::env::temp_dir().join(format!("output-{}.json", std::process::id()));
+        let mut out = open_output(&path).unwrap();
+        write_json(&mut out, &["a", "b"]).unwrap();
+        drop(out);
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "[\n  \"a\",\n  \"b\"\n]\n"
+        );
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(open_output(Path::new("/nonexistent/dir/out.json")).is_err());
+    }
+}
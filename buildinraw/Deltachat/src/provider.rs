@@ -1,348 +1,29 @@
-/// The available configuration keys.
-/*
-extern crate strum;
-#[macro_use]
-extern crate strum_macros;
-// */
+//! [Provider database](https://providers.delta.chat/) module.
 
+pub mod diff;
+pub mod markdown;
+mod parser;
 
-use strum::{EnumProperty, IntoEnumIterator};
-use strum_macros::{AsRefStr, Display, EnumIter, EnumString};
+use std::collections::HashMap;
+use std::path::Path;
 
+use anyhow::{Context as _, Result};
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumString;
 
-#[derive(
-    Debug,
-    Clone,
-    Copy,
-    PartialEq,
-    Eq,
-    Display,
-    EnumString,
-    AsRefStr,
-    EnumIter,
-    EnumProperty,
-    PartialOrd,
-    Ord,
-)]
-#[strum(serialize_all = "snake_case")]
-pub enum Config {
-    /// Email address, used in the `From:` field.
-    Addr,
-
-    /// IMAP server hostname.
-    MailServer,
-
-    /// IMAP server username.
-    MailUser,
-
-    /// IMAP server password.
-    MailPw,
-
-    /// IMAP server port.
-    MailPort,
-
-    /// IMAP server security (e.g. TLS, STARTTLS).
-    MailSecurity,
-
-    /// How to check IMAP server TLS certificates.
-    ImapCertificateChecks,
-
-    /// SMTP server hostname.
-    SendServer,
-
-    /// SMTP server username.
-    SendUser,
-
-    /// SMTP server password.
-    SendPw,
-
-    /// SMTP server port.
-    SendPort,
-
-    /// SMTP server security (e.g. TLS, STARTTLS).
-    SendSecurity,
-
-    /// How to check SMTP server TLS certificates.
-    SmtpCertificateChecks,
-
-    /// Whether to use OAuth 2.
-    ///
-    /// Historically contained other bitflags, which are now deprecated.
-    /// Should not be extended in the future, create new config keys instead.
-    ServerFlags,
-
-    /// True if SOCKS5 is enabled.
-    ///
-    /// Can be used to disable SOCKS5 without erasing SOCKS5 configuration.
-    Socks5Enabled,
-
-    /// SOCKS5 proxy server hostname or address.
-    Socks5Host,
-
-    /// SOCKS5 proxy server port.
-    Socks5Port,
-
-    /// SOCKS5 proxy server username.
-    Socks5User,
-
-    /// SOCKS5 proxy server password.
-    Socks5Password,
-
-    /// Own name to use in the `From:` field when sending messages.
-    Displayname,
-
-    /// Own status to display, sent in message footer.
-    Selfstatus,
-
-    /// Own avatar filename.
-    Selfavatar,
-
-    /// Send BCC copy to self.
-    ///
-    /// Should be enabled for multidevice setups.
-    #[strum(props(default = "1"))]
-    BccSelf,
-
-    /// True if encryption is preferred according to Autocrypt standard.
-    #[strum(props(default = "1"))]
-    E2eeEnabled,
-
-    /// True if Message Delivery Notifications (read receipts) should
-    /// be sent and requested.
-    #[strum(props(default = "1"))]
-    MdnsEnabled,
-
-    /// True if "Sent" folder should be watched for changes.
-    #[strum(props(default = "0"))]
-    SentboxWatch,
-
-    /// True if chat messages should be moved to a separate folder.
-    #[strum(props(default = "1"))]
-    MvboxMove,
-
-    /// Watch for new messages in the "Mvbox" (aka DeltaChat folder) only.
-    ///
-    /// This will not entirely disable other folders, e.g. the spam folder will also still
-    /// be watched for new messages.
-    #[strum(props(default = "0"))]
-    OnlyFetchMvbox,
-
-    /// Whether to show classic emails or only chat messages.
-    #[strum(props(default = "2"))] // also change ShowEmails.default() on changes
-    ShowEmails,
-
-    /// Quality of the media files to send.
-    #[strum(props(default = "0"))] // also change MediaQuality.default() on changes
-    MediaQuality,
-
-    /// If set to "1", on the first time `start_io()` is called after configuring,
-    /// the newest existing messages are fetched.
-    /// Existing recipients are added to the contact database regardless of this setting.
-    #[strum(props(default = "0"))]
-    FetchExistingMsgs,
-
-    /// If set to "1", then existing messages are considered to be already fetched.
-    /// This flag is reset after successful configuration.
-    #[strum(props(default = "1"))]
-    FetchedExistingMsgs,
-
-    /// Type of the OpenPGP key to generate.
-    #[strum(props(default = "0"))]
-    KeyGenType,
-
-    /// Timer in seconds after which the message is deleted from the
-    /// server.
-    ///
-    /// Equals to 0 by default, which means the message is never
-    /// deleted.
-    ///
-    /// Value 1 is treated as "delete at once": messages are deleted
-    /// immediately, without moving to DeltaChat folder.
-    #[strum(props(default = "0"))]
-    DeleteServerAfter,
-
-    /// Timer in seconds after which the message is deleted from the
-    /// device.
-    ///
-    /// Equals to 0 by default, which means the message is never
-    /// deleted.
-    #[strum(props(default = "0"))]
-    DeleteDeviceAfter,
-
-    /// Move messages to the Trash folder instead of marking them "\Deleted". Overrides
-    /// `ProviderOptions::delete_to_trash`.
-    DeleteToTrash,
-
-    /// Save raw MIME messages with headers in the database if true.
-    SaveMimeHeaders,
-
-    /// The primary email address. Also see `SecondaryAddrs`.
-    ConfiguredAddr,
-
-    /// Configured IMAP server hostname.
-    ConfiguredMailServer,
-
-    /// Configured IMAP server username.
-    ConfiguredMailUser,
-
-    /// Configured IMAP server password.
-    ConfiguredMailPw,
-
-    /// Configured IMAP server port.
-    ConfiguredMailPort,
-
-    /// Configured IMAP server security (e.g. TLS, STARTTLS).
-    ConfiguredMailSecurity,
-
-    /// How to check IMAP server TLS certificates.
-    ConfiguredImapCertificateChecks,
-
-    /// Configured SMTP server hostname.
-    ConfiguredSendServer,
-
-    /// Configured SMTP server username.
-    ConfiguredSendUser,
-
-    /// Configured SMTP server password.
-    ConfiguredSendPw,
-
-    /// Configured SMTP server port.
-    ConfiguredSendPort,
-
-    /// How to check SMTP server TLS certificates.
-    ConfiguredSmtpCertificateChecks,
-
-    /// Whether OAuth 2 is used with configured provider.
-    ConfiguredServerFlags,
-
-    /// Configured SMTP server security (e.g. TLS, STARTTLS).
-    ConfiguredSendSecurity,
-
-    /// Configured folder for incoming messages.
-    ConfiguredInboxFolder,
-
-    /// Configured folder for chat messages.
-    ConfiguredMvboxFolder,
-
-    /// Configured "Sent" folder.
-    ConfiguredSentboxFolder,
-
-    /// Configured "Trash" folder.
-    ConfiguredTrashFolder,
-
-    /// Unix timestamp of the last successful configuration.
-    ConfiguredTimestamp,
-
-    /// ID of the configured provider from the provider database.
-    ConfiguredProvider,
-
-    /// True if account is configured.
-    Configured,
-
-    /// All secondary self addresses separated by spaces
-    /// (`addr1@example.org addr2@example.org addr3@example.org`)
-    SecondaryAddrs,
-
-    /// Read-only core version string.
-    #[strum(serialize = "sys.version")]
-    SysVersion,
-
-    /// Maximal recommended attachment size in bytes.
-    #[strum(serialize = "sys.msgsize_max_recommended")]
-    SysMsgsizeMaxRecommended,
-
-    /// Space separated list of all config keys available.
-    #[strum(serialize = "sys.config_keys")]
-    SysConfigKeys,
-
-    /// True if it is a bot account.
-    Bot,
+use crate::config::{Config, ConfigValue};
+use crate::email::{AddressError, EmailAddress};
+use crate::idn;
+use crate::mx::MxSource;
+use crate::psl;
+use crate::schema::{Authentication, OAuth2Descriptor};
 
-    /// True when to skip initial start messages in groups.
-    #[strum(props(default = "0"))]
-    SkipStartMessages,
-
-    /// Whether we send a warning if the password is wrong (set to false when we send a warning
-    /// because we do not want to send a second warning)
-    #[strum(props(default = "0"))]
-    NotifyAboutWrongPw,
-
-    /// If a warning about exceeding quota was shown recently,
-    /// this is the percentage of quota at the time the warning was given.
-    /// Unset, when quota falls below minimal warning threshold again.
-    QuotaExceeding,
-
-    /// address to webrtc instance to use for videochats
-    WebrtcInstance,
-
-    /// Timestamp of the last time housekeeping was run
-    LastHousekeeping,
-
-    /// Timestamp of the last `CantDecryptOutgoingMsgs` notification.
-    LastCantDecryptOutgoingMsgs,
-
-    /// To how many seconds to debounce scan_all_folders. Used mainly in tests, to disable debouncing completely.
-    #[strum(props(default = "60"))]
-    ScanAllFoldersDebounceSecs,
-
-    /// Whether to avoid using IMAP IDLE even if the server supports it.
-    ///
-    /// This is a developer option for testing "fake idle".
-    #[strum(props(default = "0"))]
-    DisableIdle,
-
-    /// Defines the max. size (in bytes) of messages downloaded automatically.
-    /// 0 = no limit.
-    #[strum(props(default = "0"))]
-    DownloadLimit,
-
-    /// Enable sending and executing (applying) sync messages. Sending requires `BccSelf` to be set.
-    #[strum(props(default = "1"))]
-    SyncMsgs,
-
-    /// Space-separated list of all the authserv-ids which we believe
-    /// may be the one of our email server.
-    ///
-    /// See `crate::authres::update_authservid_candidates`.
-    AuthservIdCandidates,
-
-    /// Make all outgoing messages with Autocrypt header "multipart/signed".
-    SignUnencrypted,
-
-    /// Let the core save all events to the database.
-    /// This value is used internally to remember the MsgId of the logging xdc
-    #[strum(props(default = "0"))]
-    DebugLogging,
-
-    /// Last message processed by the bot.
-    LastMsgId,
-
-    /// How often to gossip Autocrypt keys in chats with multiple recipients, in seconds. 2 days by
-    /// default.
-    ///
-    /// This is not supposed to be changed by UIs and only used for testing.
-    #[strum(props(default = "172800"))]
-    GossipPeriod,
-
-    /// Feature flag for verified 1:1 chats; the UI should set it
-    /// to 1 if it supports verified 1:1 chats.
-    /// Regardless of this setting, `chat.is_protected()` returns true while the key is verified,
-    /// and when the key changes, an info message is posted into the chat.
-    /// 0=Nothing else happens when the key changes.
-    /// 1=After the key changed, `can_send()` returns false and `is_protection_broken()` returns true
-    /// until `chat_id.accept()` is called.
-    #[strum(props(default = "0"))]
-    VerifiedOneOnOneChats,
-
-    /// Row ID of the key in the `keypairs` table
-    /// used for signatures, encryption to self and included in `Autocrypt` header.
-    KeyId,
-
-    /// This key is sent to the self_reporting bot so that the bot can recognize the user
-    /// without storing the email address
-    SelfReportingId,
-}
+/// Upstream `src/provider/data.rs` snapshot bundled with the crate.
+const BUNDLED_DATA: &str = include_str!("data.rs");
 
+/// Provider status according to manual testing.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, EnumString, ValueEnum)]
 pub enum Status {
     /// Provider is known to be working with Delta Chat.
     Ok = 1,
@@ -355,15 +36,58 @@ pub enum Status {
     Broken = 3,
 }
 
+/// Server protocol.
+#[derive(
+    Debug, Serialize, Deserialize, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, EnumString,
+)]
 pub enum Protocol {
     /// SMTP protocol.
     Smtp = 1,
 
     /// IMAP protocol.
     Imap = 2,
+
+    /// POP3 protocol, not used by Delta Chat but by other client lists.
+    Pop3 = 3,
+
+    /// JMAP (RFC 8620) over HTTPS, not used by Delta Chat.
+    Jmap = 4,
+
+    /// Exchange Web Services, as in Autodiscover and Thunderbird `exchange`
+    /// servers.
+    Ews = 5,
+
+    /// Exchange ActiveSync.
+    ActiveSync = 6,
+}
+
+/// Whether a server receives or sends mail for the client.
+#[derive(Debug, Serialize, Copy, Clone, PartialEq, Eq)]
+pub enum Direction {
+    Incoming,
+    Outgoing,
+}
+
+impl Protocol {
+    /// Direction of servers of this protocol.
+    ///
+    /// JMAP and the Exchange protocols send mail too, but like Thunderbird
+    /// they are listed as incoming servers: the account is configured by
+    /// its mailbox access.
+    pub fn direction(self) -> Direction {
+        match self {
+            Protocol::Smtp => Direction::Outgoing,
+            Protocol::Imap
+            | Protocol::Pop3
+            | Protocol::Jmap
+            | Protocol::Ews
+            | Protocol::ActiveSync => Direction::Incoming,
+        }
+    }
 }
 
-#[derive(Default)]
+/// Socket security.
+#[derive(Debug, Default, Serialize, Deserialize, Copy, Clone, PartialEq, Eq, EnumString)]
 pub enum Socket {
     /// Unspecified socket security, select automatically.
     #[default]
@@ -379,6 +103,8 @@ pub enum Socket {
     Plain = 3,
 }
 
+/// Pattern used to construct login usernames from email addresses.
+#[derive(Debug, Serialize, Copy, Clone, PartialEq, Eq, EnumString)]
 pub enum UsernamePattern {
     /// Whole email is used as username.
     Email = 1,
@@ -387,6 +113,32 @@ pub enum UsernamePattern {
     Emaillocalpart = 2,
 }
 
+impl UsernamePattern {
+    /// Thunderbird autoconfig placeholder standing for the pattern.
+    pub fn placeholder(self) -> &'static str {
+        match self {
+            UsernamePattern::Email => "%EMAILADDRESS%",
+            UsernamePattern::Emaillocalpart => "%EMAILLOCALPART%",
+        }
+    }
+
+    /// Login name for `email`.
+    ///
+    /// The local part is everything before the last `@`, so quoted local
+    /// parts containing `@` are kept whole. An address without `@` is
+    /// returned as is.
+    pub fn apply(self, email: &str) -> &str {
+        match self {
+            UsernamePattern::Email => email,
+            UsernamePattern::Emaillocalpart => email
+                .rsplit_once('@')
+                .map_or(email, |(local_part, _)| local_part),
+        }
+    }
+}
+
+/// Type of OAuth 2 authorization.
+#[derive(Debug, Serialize, Copy, Clone, PartialEq, Eq, EnumString)]
 pub enum Oauth2Authorizer {
     /// Yandex.
     Yandex = 1,
@@ -395,6 +147,40 @@ pub enum Oauth2Authorizer {
     Gmail = 2,
 }
 
+impl Oauth2Authorizer {
+    /// Authorization Delta Chat performs, from upstream `src/oauth2.rs`.
+    ///
+    /// The redirect URI is chosen by each Delta Chat app, so it is not set.
+    pub fn descriptor(self) -> OAuth2Descriptor {
+        let (authorization, token, scopes, client_id, parameter) = match self {
+            Oauth2Authorizer::Gmail => (
+                "https://accounts.google.com/o/oauth2/auth",
+                "https://accounts.google.com/o/oauth2/token",
+                &["https://mail.google.com/", "email"][..],
+                "959970109878-4mvtgf6feshskf7695nfln6002mom908.apps.googleusercontent.com",
+                ("access_type", "offline"),
+            ),
+            Oauth2Authorizer::Yandex => (
+                "https://oauth.yandex.com/authorize",
+                "https://oauth.yandex.com/token",
+                &["mail:imap_full", "mail:smtp"][..],
+                "c4d0b6735fc8420a816d7e1303469341",
+                ("force_confirm", "true"),
+            ),
+        };
+        OAuth2Descriptor {
+            authorization_endpoint: authorization.to_string(),
+            token_endpoint: token.to_string(),
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+            client_id: Some(client_id.to_string()),
+            redirect_uri: None,
+            parameters: [(parameter.0.to_string(), parameter.1.to_string())].into(),
+        }
+    }
+}
+
+/// Email server endpoint.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
 pub struct Server {
     /// Server protocol, e.g. SMTP or IMAP.
     pub protocol: Protocol,
@@ -403,7 +189,7 @@ pub struct Server {
     pub socket: Socket,
 
     /// Server host.
-    pub hostname: &'static str,
+    pub hostname: String,
 
     /// Server port.
     pub port: u16,
@@ -412,35 +198,53 @@ pub struct Server {
     pub username_pattern: UsernamePattern,
 }
 
+impl Server {
+    /// Login name of `email` on this server.
+    pub fn username<'a>(&self, email: &'a str) -> &'a str {
+        self.username_pattern.apply(email)
+    }
+}
+
+/// Pair of key and value for default configuration.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
 pub struct ConfigDefault {
     /// Configuration variable name.
     pub key: Config,
 
     /// Configuration variable value.
-    pub value: &'static str,
+    pub value: String,
+}
+
+impl ConfigDefault {
+    /// The value read as the key's type.
+    pub fn typed_value(&self) -> Result<ConfigValue> {
+        self.key.parse_value(&self.value)
+    }
 }
 
+/// Provider database entry.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
 pub struct Provider {
     /// Unique ID, corresponding to provider database filename.
-    pub id: &'static str,
+    pub id: String,
 
     /// Provider status according to manual testing.
     pub status: Status,
 
     /// Hint to be shown to the user on the login screen.
-    pub before_login_hint: &'static str,
+    pub before_login_hint: String,
 
     /// Hint to be added to the device chat after provider configuration.
-    pub after_login_hint: &'static str,
+    pub after_login_hint: String,
 
     /// URL of the page with provider overview.
-    pub overview_page: &'static str,
+    pub overview_page: String,
 
     /// List of provider servers.
-    pub server: &'static [Server],
+    pub server: Vec<Server>,
 
     /// Default configuration values to set when provider is configured.
-    pub config_defaults: Option<&'static [ConfigDefault]>,
+    pub config_defaults: Option<Vec<ConfigDefault>>,
 
     /// Type of OAuth 2 authorization if provider supports it.
     pub oauth2_authorizer: Option<Oauth2Authorizer>,
@@ -449,6 +253,20 @@ pub struct Provider {
     pub opt: ProviderOptions,
 }
 
+impl Provider {
+    /// Authentication methods Delta Chat uses with the servers: OAuth 2 if
+    /// the provider has an authorizer, and the password sent as is.
+    pub fn authentication(&self) -> Vec<Authentication> {
+        self.oauth2_authorizer
+            .map(|_| Authentication::OAuth2)
+            .into_iter()
+            .chain([Authentication::PasswordCleartext])
+            .collect()
+    }
+}
+
+/// Provider options with good defaults.
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
 pub struct ProviderOptions {
     /// True if provider is known to use use proper,
     /// not self-signed certificates.
@@ -460,3 +278,447 @@ pub struct ProviderOptions {
     /// Move messages to the Trash folder instead of marking them "\Deleted".
     pub delete_to_trash: bool,
 }
+
+impl ProviderOptions {
+    pub const fn new() -> Self {
+        Self {
+            strict_tls: true,
+            max_smtp_rcpt_to: None,
+            delete_to_trash: false,
+        }
+    }
+}
+
+impl Default for ProviderOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Options of [`ProviderDb::get_provider_by_mx`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MxOptions {
+    /// Only consider Gmail, as upstream does for security reasons.
+    ///
+    /// Turning this off shows which other providers would match by MX.
+    pub gmail_only: bool,
+}
+
+impl MxOptions {
+    pub const fn new() -> Self {
+        Self { gmail_only: true }
+    }
+}
+
+impl Default for MxOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// In-memory provider database, the runtime counterpart of upstream
+/// `PROVIDER_DATA`, `PROVIDER_IDS` and `_PROVIDER_UPDATED`.
+#[derive(Debug, Clone, Default)]
+pub struct ProviderDb {
+    /// Providers in the order they are defined.
+    providers: Vec<Provider>,
+
+    /// Domain patterns in `PROVIDER_DATA` order, with an index into `providers`.
+    data: Vec<(String, usize)>,
+
+    /// Provider ids, with an index into `providers`.
+    ids: HashMap<String, usize>,
+
+    /// Date of the snapshot, `YYYY-MM-DD`.
+    updated: Option<String>,
+}
+
+impl ProviderDb {
+    /// Parses the snapshot bundled with the crate.
+    pub fn bundled() -> Self {
+        Self::from_data_rs(BUNDLED_DATA).expect("bundled data.rs is valid")
+    }
+
+    /// Parses the source of any revision of upstream `src/provider/data.rs`.
+    pub fn from_data_rs(src: &str) -> Result<Self> {
+        parser::parse(src)
+    }
+
+    /// Reads and parses an upstream `data.rs` file.
+    pub fn from_data_rs_file(path: &Path) -> Result<Self> {
+        let src = std::fs::read_to_string(path)
+            .with_context(|| format!("cannot read {}", path.display()))?;
+        Self::from_data_rs(&src).with_context(|| format!("cannot parse {}", path.display()))
+    }
+
+    /// Loads a directory of provider-db Markdown files.
+    pub fn from_md_dir(dir: &Path) -> Result<Self> {
+        markdown::load_dir(dir)
+    }
+
+    /// Builds a database from providers and `(domain, provider id)` pairs.
+    ///
+    /// `PROVIDER_IDS` is derived from the provider ids. Domains are stored in
+    /// their ASCII form. Fails if a `config_defaults` value does not match
+    /// the type of its key.
+    pub fn new(
+        providers: Vec<Provider>,
+        data: Vec<(String, String)>,
+        updated: Option<String>,
+    ) -> Result<Self> {
+        for provider in &providers {
+            for default in provider.config_defaults.iter().flatten() {
+                default.typed_value().with_context(|| {
+                    format!("invalid config default of provider {:?}", provider.id)
+                })?;
+            }
+        }
+        let ids: HashMap<String, usize> = providers
+            .iter()
+            .enumerate()
+            .map(|(i, provider)| (provider.id.clone(), i))
+            .collect();
+        let data = data
+            .into_iter()
+            .map(|(domain, id)| match ids.get(&id) {
+                Some(i) => Ok((idn::normalize(&domain), *i)),
+                None => anyhow::bail!("domain {domain:?} refers to unknown provider {id:?}"),
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            providers,
+            data,
+            ids,
+            updated,
+        })
+    }
+
+    /// All providers, in definition order.
+    pub fn providers(&self) -> &[Provider] {
+        &self.providers
+    }
+
+    /// Domain patterns and their providers, in `PROVIDER_DATA` order.
+    pub fn provider_data(&self) -> impl Iterator<Item = (&str, &Provider)> {
+        self.data
+            .iter()
+            .map(|(domain, i)| (domain.as_str(), &self.providers[*i]))
+    }
+
+    /// Domain patterns mapping to the given provider.
+    pub fn domains_of<'a>(&'a self, provider: &'a Provider) -> impl Iterator<Item = &'a str> {
+        self.provider_data()
+            .filter(move |(_, p)| std::ptr::eq(*p, provider))
+            .map(|(domain, _)| domain)
+    }
+
+    /// Snapshot date from `_PROVIDER_UPDATED`, if present.
+    pub fn updated(&self) -> Option<&str> {
+        self.updated.as_deref()
+    }
+
+    /// Finds a provider in the database based on domain.
+    ///
+    /// The domain may be given with U-labels or A-labels.
+    pub fn get_provider_by_domain(&self, domain: &str) -> Option<&Provider> {
+        let domain = idn::normalize(domain);
+        for (pattern, provider) in self.provider_data() {
+            if let Some(suffix) = pattern.strip_prefix('*') {
+                // Wildcard domain pattern.
+                //
+                // For example, `suffix` is ".hermes.radio" for "*.hermes.radio" pattern.
+                if domain.ends_with(suffix) {
+                    return Some(provider);
+                }
+            } else if pattern == domain {
+                return Some(provider);
+            }
+        }
+
+        None
+    }
+
+    /// Like [`get_provider_by_domain`](ProviderDb::get_provider_by_domain),
+    /// falling back to the parent domains of `domain` up to its registered
+    /// domain, so `mail.sub.example.co.uk` finds the provider of
+    /// `example.co.uk`. Returns the domain that matched too.
+    pub fn get_provider_by_domain_or_parent(&self, domain: &str) -> Option<(String, &Provider)> {
+        let domain = idn::normalize(domain);
+        std::iter::once(domain.clone())
+            .chain(psl::parent_domains(&domain))
+            .find_map(|d| Some((d.clone(), self.get_provider_by_domain(&d)?)))
+    }
+
+    /// Finds a provider based on MX record for the given domain.
+    ///
+    /// A provider matches if an MX host is one of its domains or a subdomain
+    /// of one; wildcard patterns are skipped. Lookup errors count as no match.
+    pub fn get_provider_by_mx(
+        &self,
+        mx: &dyn MxSource,
+        domain: &str,
+        options: MxOptions,
+    ) -> Option<&Provider> {
+        let Ok(mx_domains) = mx.mx_lookup(domain) else {
+            return None;
+        };
+
+        for (provider_domain_pattern, provider) in self.provider_data() {
+            if options.gmail_only && provider.id != "gmail" {
+                // MX lookup is limited to Gmail for security reasons
+                continue;
+            }
+
+            if provider_domain_pattern.starts_with('*') {
+                // Skip wildcard patterns.
+                continue;
+            }
+
+            let provider_domain_dot = format!(".{provider_domain_pattern}");
+            for mx_domain in &mx_domains {
+                let mx_domain = &mx_domain.exchange;
+                if mx_domain == provider_domain_pattern || mx_domain.ends_with(&provider_domain_dot)
+                {
+                    return Some(provider);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Finds a provider by domain, then, unless `skip_mx` is set, by MX.
+    pub fn get_provider_info(
+        &self,
+        mx: &dyn MxSource,
+        domain: &str,
+        skip_mx: bool,
+        options: MxOptions,
+    ) -> Option<&Provider> {
+        if let Some(provider) = self.get_provider_by_domain(domain) {
+            return Some(provider);
+        }
+
+        if !skip_mx {
+            if let Some(provider) = self.get_provider_by_mx(mx, domain, options) {
+                return Some(provider);
+            }
+        }
+
+        None
+    }
+
+    /// Like [`get_provider_by_domain`](ProviderDb::get_provider_by_domain),
+    /// for the domain of an email address.
+    pub fn get_provider_by_addr(&self, addr: &str) -> Result<Option<&Provider>, AddressError> {
+        let addr = EmailAddress::new(addr)?;
+        Ok(self.get_provider_by_domain(&addr.domain))
+    }
+
+    /// Like [`get_provider_info`](ProviderDb::get_provider_info), for the
+    /// domain of an email address.
+    pub fn get_provider_info_by_addr(
+        &self,
+        mx: &dyn MxSource,
+        addr: &str,
+        skip_mx: bool,
+        options: MxOptions,
+    ) -> Result<Option<&Provider>, AddressError> {
+        let addr = EmailAddress::new(addr)?;
+        Ok(self.get_provider_info(mx, &addr.domain, skip_mx, options))
+    }
+
+    /// Returns a provider with the given ID from the database.
+    pub fn get_provider_by_id(&self, id: &str) -> Option<&Provider> {
+        self.ids.get(id).map(|i| &self.providers[*i])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mx::StaticMx;
+
+    #[test]
+    fn test_get_provider_by_domain_unexistant() {
+        let db = ProviderDb::bundled();
+        assert!(db.get_provider_by_domain("unexistant.org").is_none());
+    }
+
+    #[test]
+    fn test_get_provider_by_domain_mixed_case() {
+        let db = ProviderDb::bundled();
+        let provider = db.get_provider_by_domain("nAUta.Cu").unwrap();
+        assert!(provider.status == Status::Ok);
+    }
+
+    #[test]
+    fn test_get_provider_by_domain() {
+        let db = ProviderDb::bundled();
+        let provider = db.get_provider_by_domain("nauta.cu").unwrap();
+        assert!(provider.status == Status::Ok);
+        let server = &provider.server[0];
+        assert_eq!(server.protocol, Protocol::Imap);
+        assert_eq!(server.socket, Socket::Starttls);
+        assert_eq!(server.hostname, "imap.nauta.cu");
+        assert_eq!(server.port, 143);
+        assert_eq!(server.username_pattern, UsernamePattern::Email);
+        let server = &provider.server[1];
+        assert_eq!(server.protocol, Protocol::Smtp);
+        assert_eq!(server.socket, Socket::Starttls);
+        assert_eq!(server.hostname, "smtp.nauta.cu");
+        assert_eq!(server.port, 25);
+        assert_eq!(server.username_pattern, UsernamePattern::Email);
+
+        let provider = db.get_provider_by_domain("gmail.com").unwrap();
+        assert!(provider.status == Status::Preparation);
+        assert!(!provider.before_login_hint.is_empty());
+        assert!(!provider.overview_page.is_empty());
+
+        let provider = db.get_provider_by_domain("googlemail.com").unwrap();
+        assert!(provider.status == Status::Preparation);
+    }
+
+    #[test]
+    fn test_get_provider_by_domain_wildcard() {
+        let db = ProviderDb::bundled();
+        let provider = db.get_provider_by_domain("team.hermes.radio").unwrap();
+        assert_eq!(provider.id, "hermes.radio");
+    }
+
+    #[test]
+    fn test_get_provider_by_mx() {
+        let db = ProviderDb::bundled();
+        let mx: StaticMx = [
+            ("hosted.example", 10, "aspmx.l.google.com."),
+            ("posteo-hosted.example", 10, "mx01.posteo.de."),
+        ]
+        .into_iter()
+        .collect();
+
+        let provider = db
+            .get_provider_by_mx(&mx, "hosted.example", MxOptions::new())
+            .unwrap();
+        assert_eq!(provider.id, "gmail");
+        assert!(db
+            .get_provider_by_mx(&mx, "posteo-hosted.example", MxOptions::new())
+            .is_none());
+
+        let options = MxOptions { gmail_only: false };
+        let provider = db
+            .get_provider_by_mx(&mx, "posteo-hosted.example", options)
+            .unwrap();
+        assert_eq!(provider.id, "posteo");
+
+        let provider = db
+            .get_provider_info(&mx, "hosted.example", false, MxOptions::new())
+            .unwrap();
+        assert_eq!(provider.id, "gmail");
+        assert!(db
+            .get_provider_info(&mx, "hosted.example", true, MxOptions::new())
+            .is_none());
+    }
+
+    #[test]
+    fn test_get_provider_by_id() {
+        let db = ProviderDb::bundled();
+        let provider = db.get_provider_by_id("gmail").unwrap();
+        assert!(provider.id == "gmail");
+    }
+
+    #[test]
+    fn test_protocol_direction() {
+        assert_eq!(Protocol::Smtp.direction(), Direction::Outgoing);
+        for protocol in [
+            Protocol::Imap,
+            Protocol::Pop3,
+            Protocol::Jmap,
+            Protocol::Ews,
+            Protocol::ActiveSync,
+        ] {
+            assert_eq!(protocol.direction(), Direction::Incoming, "{protocol:?}");
+        }
+    }
+
+    #[test]
+    fn test_username() {
+        let db = ProviderDb::bundled();
+        let gmail = db.get_provider_by_id("gmail").unwrap();
+        assert_eq!(
+            gmail.server[0].username("alice@gmail.com"),
+            "alice@gmail.com"
+        );
+        let qq = db.get_provider_by_id("qq").unwrap();
+        assert_eq!(qq.server[0].username("12345@qq.com"), "12345");
+        assert_eq!(
+            qq.server[0].username_pattern.placeholder(),
+            "%EMAILLOCALPART%"
+        );
+        assert_eq!(
+            UsernamePattern::Emaillocalpart.apply("\"a@b\"@example.org"),
+            "\"a@b\""
+        );
+    }
+
+    #[test]
+    fn test_get_provider_by_addr() {
+        let db = ProviderDb::bundled();
+        let provider = db.get_provider_by_addr("Alice+x@GMail.com").unwrap();
+        assert_eq!(provider.unwrap().id, "gmail");
+        assert!(db
+            .get_provider_by_addr("alice@unexistant.org")
+            .unwrap()
+            .is_none());
+        assert_eq!(
+            db.get_provider_by_addr("gmail.com"),
+            Err(AddressError::MissingAt)
+        );
+
+        let mx: StaticMx = [("hosted.example", 5, "aspmx.l.google.com")]
+            .into_iter()
+            .collect();
+        let provider = db
+            .get_provider_info_by_addr(&mx, "bob@hosted.example", false, MxOptions::new())
+            .unwrap();
+        assert_eq!(provider.unwrap().id, "gmail");
+    }
+
+    #[test]
+    fn test_get_provider_by_idn() {
+        let bundled = ProviderDb::bundled();
+        let db = ProviderDb::new(
+            bundled.providers().to_vec(),
+            vec![("Bücher.de".to_string(), "gmail".to_string())],
+            None,
+        )
+        .unwrap();
+        for domain in ["bücher.de", "BÜCHER.DE.", "xn--bcher-kva.de"] {
+            assert!(db.get_provider_by_domain(domain).is_some(), "{domain}");
+        }
+        assert!(db.get_provider_by_addr("user@bücher.de").unwrap().is_some());
+    }
+
+    #[test]
+    fn test_invalid_config_default() {
+        let bundled = ProviderDb::bundled();
+        let mut providers = bundled.providers().to_vec();
+        let testrun = providers.iter_mut().find(|p| p.id == "testrun").unwrap();
+        let defaults = testrun.config_defaults.as_mut().unwrap();
+        assert_eq!(defaults[0].typed_value().unwrap().as_bool(), Some(true));
+        defaults[0].value = "yes".to_string();
+        let err = ProviderDb::new(providers, Vec::new(), None).unwrap_err();
+        assert!(format!("{err:#}").contains("bcc_self"), "{err:#}");
+    }
+
+    #[test]
+    fn test_get_provider_by_parent_domain() {
+        let db = ProviderDb::bundled();
+        assert!(db.get_provider_by_domain("mail.sub.gmail.com").is_none());
+        let (domain, provider) = db
+            .get_provider_by_domain_or_parent("mail.sub.gmail.com")
+            .unwrap();
+        assert_eq!(domain, "gmail.com");
+        assert_eq!(provider.id, "gmail");
+        assert!(db.get_provider_by_domain_or_parent("com").is_none());
+    }
+}
@@ -0,0 +1,499 @@
+//! Differences between two snapshots of the provider database.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use super::{
+    ConfigDefault, Oauth2Authorizer, Provider, ProviderDb, ProviderOptions, Server, Status,
+};
+use crate::config::Config;
+
+/// Provider id that disappeared while its domains moved to a new id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Rename {
+    pub from: String,
+    pub to: String,
+}
+
+/// Domain of `PROVIDER_DATA` whose provider changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MovedDomain {
+    pub domain: String,
+    pub from: String,
+    pub to: String,
+}
+
+/// Domain of `PROVIDER_DATA` that was added or removed, with its provider id.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DomainChange {
+    pub domain: String,
+    pub provider: String,
+}
+
+/// Change of one provider field.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "field", rename_all = "snake_case")]
+pub enum Change {
+    Status {
+        old: Status,
+        new: Status,
+    },
+
+    /// Servers are compared as a whole, so reordering counts as a change;
+    /// `added` and `removed` list the servers only in one snapshot.
+    Server {
+        added: Vec<Server>,
+        removed: Vec<Server>,
+    },
+    Opt {
+        old: ProviderOptions,
+        new: ProviderOptions,
+    },
+    ConfigDefaults {
+        old: Option<Vec<ConfigDefault>>,
+        new: Option<Vec<ConfigDefault>>,
+    },
+    Oauth2Authorizer {
+        old: Option<Oauth2Authorizer>,
+        new: Option<Oauth2Authorizer>,
+    },
+    BeforeLoginHint {
+        old: String,
+        new: String,
+    },
+    AfterLoginHint {
+        old: String,
+        new: String,
+    },
+}
+
+impl Change {
+    fn describe(&self) -> String {
+        match self {
+            Change::Status { old, new } => format!("status {old:?} -> {new:?}"),
+            Change::Server { added, removed } => {
+                let servers = |servers: &[Server]| {
+                    servers
+                        .iter()
+                        .map(|s| {
+                            format!("{:?} {}:{} {:?}", s.protocol, s.hostname, s.port, s.socket)
+                        })
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                };
+                match (added.is_empty(), removed.is_empty()) {
+                    (true, true) => "servers reordered".to_string(),
+                    (false, true) => format!("servers added: {}", servers(added)),
+                    (true, false) => format!("servers removed: {}", servers(removed)),
+                    (false, false) => format!(
+                        "servers removed: {}; added: {}",
+                        servers(removed),
+                        servers(added)
+                    ),
+                }
+            }
+            Change::Opt { old, new } => format!("opt {old:?} -> {new:?}"),
+            Change::ConfigDefaults { old, new } => {
+                format!("config_defaults {old:?} -> {new:?}")
+            }
+            Change::Oauth2Authorizer { old, new } => format!("oauth2 {old:?} -> {new:?}"),
+            Change::BeforeLoginHint { .. } => "before_login_hint changed".to_string(),
+            Change::AfterLoginHint { .. } => "after_login_hint changed".to_string(),
+        }
+    }
+}
+
+/// Changed provider, by its id in the new snapshot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProviderChange {
+    pub id: String,
+    pub changes: Vec<Change>,
+}
+
+/// Differences between an old and a new provider database.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SnapshotDiff {
+    /// `_PROVIDER_UPDATED` of the old snapshot.
+    pub old_updated: Option<String>,
+
+    /// `_PROVIDER_UPDATED` of the new snapshot.
+    pub new_updated: Option<String>,
+
+    /// Provider ids only in the new snapshot, renames excluded.
+    pub added: Vec<String>,
+
+    /// Provider ids only in the old snapshot, renames excluded.
+    pub removed: Vec<String>,
+
+    /// Removed ids whose domains now mostly belong to an added id.
+    pub renamed: Vec<Rename>,
+
+    /// Domains now mapping to another provider, renames excluded.
+    pub moved_domains: Vec<MovedDomain>,
+
+    /// Domains only in the new snapshot.
+    pub added_domains: Vec<DomainChange>,
+
+    /// Domains only in the old snapshot.
+    pub removed_domains: Vec<DomainChange>,
+
+    /// Providers in both snapshots, renamed ones included, that changed.
+    pub changed: Vec<ProviderChange>,
+}
+
+fn domain_map(db: &ProviderDb) -> BTreeMap<&str, &str> {
+    db.provider_data()
+        .map(|(domain, provider)| (domain, provider.id.as_str()))
+        .collect()
+}
+
+/// Config defaults by key; their order in the snapshot does not matter.
+fn config_defaults(provider: &Provider) -> Option<BTreeMap<Config, &str>> {
+    provider
+        .config_defaults
+        .as_ref()
+        .map(|defaults| defaults.iter().map(|d| (d.key, d.value.as_str())).collect())
+}
+
+fn compare(old: &Provider, new: &Provider) -> Vec<Change> {
+    let mut changes = Vec::new();
+    if old.status != new.status {
+        changes.push(Change::Status {
+            old: old.status,
+            new: new.status,
+        });
+    }
+    if old.server != new.server {
+        changes.push(Change::Server {
+            added: new
+                .server
+                .iter()
+                .filter(|s| !old.server.contains(s))
+                .cloned()
+                .collect(),
+            removed: old
+                .server
+                .iter()
+                .filter(|s| !new.server.contains(s))
+                .cloned()
+                .collect(),
+        });
+    }
+    if old.opt != new.opt {
+        changes.push(Change::Opt {
+            old: old.opt.clone(),
+            new: new.opt.clone(),
+        });
+    }
+    if config_defaults(old) != config_defaults(new) {
+        changes.push(Change::ConfigDefaults {
+            old: old.config_defaults.clone(),
+            new: new.config_defaults.clone(),
+        });
+    }
+    if old.oauth2_authorizer != new.oauth2_authorizer {
+        changes.push(Change::Oauth2Authorizer {
+            old: old.oauth2_authorizer,
+            new: new.oauth2_authorizer,
+        });
+    }
+    if old.before_login_hint != new.before_login_hint {
+        changes.push(Change::BeforeLoginHint {
+            old: old.before_login_hint.clone(),
+            new: new.before_login_hint.clone(),
+        });
+    }
+    if old.after_login_hint != new.after_login_hint {
+        changes.push(Change::AfterLoginHint {
+            old: old.after_login_hint.clone(),
+            new: new.after_login_hint.clone(),
+        });
+    }
+    changes
+}
+
+impl SnapshotDiff {
+    /// Compares two snapshots.
+    pub fn new(old: &ProviderDb, new: &ProviderDb) -> Self {
+        let old_ids: BTreeSet<&str> = old.providers().iter().map(|p| p.id.as_str()).collect();
+        let new_ids: BTreeSet<&str> = new.providers().iter().map(|p| p.id.as_str()).collect();
+        let old_domains = domain_map(old);
+        let new_domains = domain_map(new);
+
+        // A removed id is renamed to the added id most of its domains moved to.
+        let added: BTreeSet<&str> = new_ids.difference(&old_ids).copied().collect();
+        let mut renamed: BTreeMap<&str, &str> = BTreeMap::new();
+        for from in old_ids.difference(&new_ids) {
+            let mut targets: BTreeMap<&str, usize> = BTreeMap::new();
+            for (domain, id) in &old_domains {
+                if id == from {
+                    if let Some(to) = new_domains.get(domain).filter(|to| added.contains(*to)) {
+                        *targets.entry(to).or_default() += 1;
+                    }
+                }
+            }
+            let best = targets
+                .into_iter()
+                .max_by_key(|(to, count)| (*count, std::cmp::Reverse(*to)));
+            if let Some((to, _)) = best {
+                if !renamed.values().any(|t| *t == to) {
+                    renamed.insert(from, to);
+                }
+            }
+        }
+
+        let mut diff = SnapshotDiff {
+            old_updated: old.updated().map(str::to_string),
+            new_updated: new.updated().map(str::to_string),
+            ..Default::default()
+        };
+        diff.added = added
+            .iter()
+            .filter(|id| !renamed.values().any(|to| to == *id))
+            .map(|id| id.to_string())
+            .collect();
+        diff.removed = old_ids
+            .difference(&new_ids)
+            .filter(|id| !renamed.contains_key(*id))
+            .map(|id| id.to_string())
+            .collect();
+        diff.renamed = renamed
+            .iter()
+            .map(|(from, to)| Rename {
+                from: from.to_string(),
+                to: to.to_string(),
+            })
+            .collect();
+
+        for (domain, from) in &old_domains {
+            match new_domains.get(domain) {
+                Some(to) if to != from && renamed.get(from) != Some(to) => {
+                    diff.moved_domains.push(MovedDomain {
+                        domain: domain.to_string(),
+                        from: from.to_string(),
+                        to: to.to_string(),
+                    })
+                }
+                Some(_) => {}
+                None => diff.removed_domains.push(DomainChange {
+                    domain: domain.to_string(),
+                    provider: from.to_string(),
+                }),
+            }
+        }
+        for (domain, provider) in &new_domains {
+            if !old_domains.contains_key(domain) {
+                diff.added_domains.push(DomainChange {
+                    domain: domain.to_string(),
+                    provider: provider.to_string(),
+                });
+            }
+        }
+
+        for new_provider in new.providers() {
+            let old_id = renamed
+                .iter()
+                .find(|(_, to)| **to == new_provider.id)
+                .map_or(new_provider.id.as_str(), |(from, _)| from);
+            let Some(old_provider) = old.get_provider_by_id(old_id) else {
+                continue;
+            };
+            let changes = compare(old_provider, new_provider);
+            if !changes.is_empty() {
+                diff.changed.push(ProviderChange {
+                    id: new_provider.id.clone(),
+                    changes,
+                });
+            }
+        }
+        diff.changed.sort_by(|a, b| a.id.cmp(&b.id));
+        diff
+    }
+
+    /// Returns true if the snapshots have the same providers and domains.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.moved_domains.is_empty()
+            && self.added_domains.is_empty()
+            && self.removed_domains.is_empty()
+            && self.changed.is_empty()
+    }
+
+    /// Writes a human-readable summary, one change per line.
+    pub fn write_summary(&self, out: &mut dyn Write) -> Result<()> {
+        let date = |d: &Option<String>| d.clone().unwrap_or_else(|| "?".to_string());
+        writeln!(
+            out,
+            "{} -> {}",
+            date(&self.old_updated),
+            date(&self.new_updated)
+        )?;
+        for id in &self.added {
+            writeln!(out, "+ {id}")?;
+        }
+        for id in &self.removed {
+            writeln!(out, "- {id}")?;
+        }
+        for rename in &self.renamed {
+            writeln!(out, "~ {} -> {}", rename.from, rename.to)?;
+        }
+        for moved in &self.moved_domains {
+            writeln!(out, "> {}: {} -> {}", moved.domain, moved.from, moved.to)?;
+        }
+        for added in &self.added_domains {
+            writeln!(out, "+ {} ({})", added.domain, added.provider)?;
+        }
+        for removed in &self.removed_domains {
+            writeln!(out, "- {} ({})", removed.domain, removed.provider)?;
+        }
+        for provider in &self.changed {
+            for change in &provider.changes {
+                writeln!(out, "* {}: {}", provider.id, change.describe())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::provider::{Protocol, Socket, UsernamePattern};
+
+    /// Bundled snapshot with `edit` applied to its providers and domains.
+    fn edited(edit: impl FnOnce(&mut Vec<Provider>, &mut Vec<(String, String)>)) -> ProviderDb {
+        let db = ProviderDb::bundled();
+        let mut providers = db.providers().to_vec();
+        let mut data: Vec<(String, String)> = db
+            .provider_data()
+            .map(|(domain, provider)| (domain.to_string(), provider.id.clone()))
+            .collect();
+        edit(&mut providers, &mut data);
+        ProviderDb::new(providers, data, Some("2024-03-01".to_string())).unwrap()
+    }
+
+    #[test]
+    fn test_identical() {
+        let db = ProviderDb::bundled();
+        let diff = SnapshotDiff::new(&db, &db);
+        assert!(diff.is_empty());
+        assert_eq!(diff.old_updated.as_deref(), Some("2024-02-05"));
+    }
+
+    #[test]
+    fn test_config_defaults_order() {
+        let old = ProviderDb::bundled();
+        let reordered = edited(|providers, _| {
+            for provider in providers {
+                if let Some(defaults) = &mut provider.config_defaults {
+                    defaults.reverse();
+                }
+            }
+        });
+        assert!(old
+            .providers()
+            .iter()
+            .any(|p| p.config_defaults.as_ref().is_some_and(|d| d.len() > 1)));
+        assert!(SnapshotDiff::new(&old, &reordered).changed.is_empty());
+
+        let changed = edited(|providers, _| {
+            let testrun = providers.iter_mut().find(|p| p.id == "testrun").unwrap();
+            let defaults = testrun.config_defaults.as_mut().unwrap();
+            let mvbox_move = defaults
+                .iter_mut()
+                .find(|d| d.key == Config::MvboxMove)
+                .unwrap();
+            mvbox_move.value = "1".to_string();
+        });
+        let diff = SnapshotDiff::new(&old, &changed);
+        assert!(matches!(
+            diff.changed[0].changes[..],
+            [Change::ConfigDefaults { .. }]
+        ));
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = ProviderDb::bundled();
+        let new = edited(|providers, data| {
+            // Rename posteo to posteo.de.
+            let posteo = providers.iter_mut().find(|p| p.id == "posteo").unwrap();
+            posteo.id = "posteo.de".to_string();
+            for (_, id) in data.iter_mut().filter(|(_, id)| id == "posteo") {
+                *id = "posteo.de".to_string();
+            }
+
+            // Change nauta.cu.
+            let nauta = providers.iter_mut().find(|p| p.id == "nauta.cu").unwrap();
+            nauta.status = Status::Broken;
+            nauta.opt.strict_tls = true;
+            nauta.server.push(Server {
+                protocol: Protocol::Imap,
+                socket: Socket::Ssl,
+                hostname: "imap.nauta.cu".to_string(),
+                port: 993,
+                username_pattern: UsernamePattern::Email,
+            });
+
+            // Move googlemail.com, drop google.com, add a provider.
+            data.iter_mut()
+                .find(|(d, _)| d == "googlemail.com")
+                .unwrap()
+                .1 = "nauta.cu".to_string();
+            data.retain(|(d, _)| d != "google.com");
+            let mut example = providers[0].clone();
+            example.id = "example.test".to_string();
+            providers.push(example);
+            data.push(("example.test".to_string(), "example.test".to_string()));
+        });
+
+        let diff = SnapshotDiff::new(&old, &new);
+        assert_eq!(diff.new_updated.as_deref(), Some("2024-03-01"));
+        assert_eq!(diff.added, ["example.test"]);
+        assert!(diff.removed.is_empty());
+        assert_eq!(
+            diff.renamed,
+            [Rename {
+                from: "posteo".to_string(),
+                to: "posteo.de".to_string()
+            }]
+        );
+        assert_eq!(
+            diff.moved_domains,
+            [MovedDomain {
+                domain: "googlemail.com".to_string(),
+                from: "gmail".to_string(),
+                to: "nauta.cu".to_string()
+            }]
+        );
+        assert_eq!(diff.removed_domains[0].domain, "google.com");
+        assert_eq!(diff.added_domains[0].domain, "example.test");
+
+        assert_eq!(diff.changed.len(), 1);
+        let nauta = &diff.changed[0];
+        assert_eq!(nauta.id, "nauta.cu");
+        assert_eq!(
+            nauta.changes[0],
+            Change::Status {
+                old: Status::Ok,
+                new: Status::Broken
+            }
+        );
+        assert!(
+            matches!(&nauta.changes[1], Change::Server { added, removed } if added.len() == 1 && removed.is_empty())
+        );
+        assert!(matches!(nauta.changes[2], Change::Opt { .. }));
+
+        let mut summary = Vec::new();
+        diff.write_summary(&mut summary).unwrap();
+        let summary = String::from_utf8(summary).unwrap();
+        assert!(summary
+            .starts_with("2024-02-05 -> 2024-03-01\n+ example.test\n~ posteo -> posteo.de\n"));
+        assert!(summary.contains("* nauta.cu: status Ok -> Broken\n"));
+    }
+}
@@ -0,0 +1,266 @@
+//! Loader for the provider-db Markdown sources.
+//!
+//! Each provider is described by a `<id>.md` file in the provider-db
+//! `_providers` directory. Its YAML front matter is what upstream turns into
+//! the generated `data.rs`; the Markdown body is documentation only.
+
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{bail, Context as _, Result};
+use serde::Deserialize;
+
+use super::{
+    ConfigDefault, Oauth2Authorizer, Protocol, Provider, ProviderDb, ProviderOptions, Server,
+    Socket, Status, UsernamePattern,
+};
+use crate::config::Config;
+
+#[derive(Debug, Deserialize)]
+struct FrontMatter {
+    status: String,
+    #[serde(default)]
+    domains: Vec<String>,
+    #[serde(default)]
+    server: Vec<ServerEntry>,
+    #[serde(default)]
+    before_login_hint: Option<String>,
+    #[serde(default)]
+    after_login_hint: Option<String>,
+    #[serde(default)]
+    oauth2: Option<String>,
+    /// Kept in file order, which `data.rs` keeps too.
+    #[serde(default)]
+    config_defaults: Option<serde_yaml::Mapping>,
+    #[serde(default)]
+    opt: Options,
+
+    /// Older files kept the options at the top level.
+    #[serde(flatten)]
+    legacy_opt: Options,
+}
+
+#[derive(Debug, Deserialize)]
+struct ServerEntry {
+    #[serde(rename = "type")]
+    protocol: String,
+    #[serde(default)]
+    socket: Option<String>,
+    hostname: String,
+    port: u16,
+    #[serde(default)]
+    username_pattern: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Options {
+    strict_tls: Option<bool>,
+    max_smtp_rcpt_to: Option<u16>,
+    delete_to_trash: Option<bool>,
+}
+
+impl Options {
+    fn apply(&self, opt: &mut ProviderOptions) {
+        if let Some(strict_tls) = self.strict_tls {
+            opt.strict_tls = strict_tls;
+        }
+        if let Some(max) = self.max_smtp_rcpt_to {
+            opt.max_smtp_rcpt_to = Some(max);
+        }
+        if let Some(delete_to_trash) = self.delete_to_trash {
+            opt.delete_to_trash = delete_to_trash;
+        }
+    }
+}
+
+/// Parses a provider-db value such as `STARTTLS` or `emaillocalpart` into the
+/// matching enum variant.
+fn variant<T: FromStr>(value: &str, variants: &[&str], what: &str) -> Result<T> {
+    variants
+        .iter()
+        .find(|v| v.eq_ignore_ascii_case(value))
+        .and_then(|v| T::from_str(v).ok())
+        .with_context(|| format!("{what}: unknown value {value:?}"))
+}
+
+/// Returns the YAML front matter of a Markdown file.
+fn front_matter(src: &str) -> Result<&str> {
+    let src = src.trim_start_matches('\u{feff}');
+    let Some(rest) = src.strip_prefix("---") else {
+        bail!("missing front matter");
+    };
+    let rest = rest.trim_start_matches(['\r', '\n']);
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return Ok(&rest[..offset]);
+        }
+        offset += line.len();
+    }
+    bail!("unterminated front matter")
+}
+
+/// Overview page URL for a provider id, e.g. `mailbox-org` for `mailbox.org`.
+pub fn overview_page(id: &str) -> String {
+    format!("https://providers.delta.chat/{}", id.replace('.', "-"))
+}
+
+/// Parses one provider file, returning the provider and its domains.
+pub fn parse_provider(id: &str, src: &str) -> Result<(Provider, Vec<String>)> {
+    let fm: FrontMatter = serde_yaml::from_str(front_matter(src)?)?;
+
+    let server = fm
+        .server
+        .iter()
+        .map(|s| {
+            Ok(Server {
+                protocol: variant::<Protocol>(&s.protocol, &["Imap", "Smtp"], "type")?,
+                socket: match &s.socket {
+                    Some(socket) => variant(socket, &["Ssl", "Starttls", "Plain"], "socket")?,
+                    None => Socket::Automatic,
+                },
+                hostname: s.hostname.clone(),
+                port: s.port,
+                username_pattern: match &s.username_pattern {
+                    Some(pattern) => {
+                        variant(pattern, &["Email", "Emaillocalpart"], "username_pattern")?
+                    }
+                    None => UsernamePattern::Email,
+                },
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    let config_defaults = match &fm.config_defaults {
+        Some(defaults) => Some(
+            defaults
+                .iter()
+                .map(|(key, value)| {
+                    let key = key
+                        .as_str()
+                        .with_context(|| format!("config default key {key:?} is not a string"))?;
+                    let key = Config::from_str(key)
+                        .ok()
+                        .with_context(|| format!("unknown config key {key:?}"))?;
+                    let value = match value {
+                        serde_yaml::Value::String(s) => s.clone(),
+                        serde_yaml::Value::Number(n) => n.to_string(),
+                        serde_yaml::Value::Bool(b) => u8::from(*b).to_string(),
+                        other => bail!("config default {key}: unsupported value {other:?}"),
+                    };
+                    Ok(ConfigDefault { key, value })
+                })
+                .collect::<Result<_>>()?,
+        ),
+        None => None,
+    };
+
+    let mut opt = ProviderOptions::new();
+    fm.legacy_opt.apply(&mut opt);
+    fm.opt.apply(&mut opt);
+
+    let provider = Provider {
+        id: id.to_string(),
+        status: variant::<Status>(&fm.status, &["Ok", "Preparation", "Broken"], "status")?,
+        before_login_hint: fm.before_login_hint.unwrap_or_default().trim().to_string(),
+        after_login_hint: fm.after_login_hint.unwrap_or_default().trim().to_string(),
+        overview_page: overview_page(id),
+        server,
+        config_defaults,
+        oauth2_authorizer: match &fm.oauth2 {
+            Some(oauth2) => Some(variant::<Oauth2Authorizer>(
+                oauth2,
+                &["Yandex", "Gmail"],
+                "oauth2",
+            )?),
+            None => None,
+        },
+        opt,
+    };
+    Ok((provider, fm.domains))
+}
+
+/// Loads every `*.md` file of a provider-db `_providers` directory.
+///
+/// Files are read in name order, which is also the order upstream generates
+/// `data.rs` in.
+pub(super) fn load_dir(dir: &Path) -> Result<ProviderDb> {
+    let mut paths = std::fs::read_dir(dir)
+        .with_context(|| format!("cannot read {}", dir.display()))?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<std::io::Result<Vec<_>>>()?;
+    paths.retain(|p| p.extension().is_some_and(|ext| ext == "md"));
+    paths.sort();
+
+    let mut providers = Vec::new();
+    let mut data = Vec::new();
+    for path in paths {
+        let Some(id) = path.file_stem().and_then(|s| s.to_str()) else {
+            continue;
+        };
+        let src = std::fs::read_to_string(&path)
+            .with_context(|| format!("cannot read {}", path.display()))?;
+        let (provider, domains) =
+            parse_provider(id, &src).with_context(|| format!("in {}", path.display()))?;
+        data.extend(domains.into_iter().map(|d| (d, provider.id.clone())));
+        providers.push(provider);
+    }
+    ProviderDb::new(providers, data, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_provider() {
+        let src = "---
+name: Testrun
+status: OK
+domains:
+  - testrun.org
+server:
+  - type: imap
+    socket: SSL
+    hostname: testrun.org
+    port: 993
+  - type: smtp
+    socket: STARTTLS
+    hostname: testrun.org
+    port: 587
+    username_pattern: EMAILLOCALPART
+config_defaults:
+  mvbox_move: 0
+  bcc_self: 1
+opt:
+  max_smtp_rcpt_to: 50
+---
+Some *documentation*.
+";
+        let (provider, domains) = parse_provider("testrun", src).unwrap();
+        let db = ProviderDb::bundled();
+        let upstream = db.get_provider_by_id("testrun").unwrap();
+        assert_eq!(domains, vec!["testrun.org"]);
+        assert_eq!(provider.overview_page, upstream.overview_page);
+        assert_eq!(provider.status, Status::Ok);
+        assert_eq!(provider.server[1].socket, Socket::Starttls);
+        assert_eq!(
+            provider.server[1].username_pattern,
+            UsernamePattern::Emaillocalpart
+        );
+        assert_eq!(provider.opt.max_smtp_rcpt_to, Some(50));
+        let defaults = provider.config_defaults.unwrap();
+        assert_eq!(defaults[0].key, Config::MvboxMove);
+        assert_eq!(defaults[0].value, "0");
+        assert_eq!(defaults[1].key, Config::BccSelf);
+    }
+
+    #[test]
+    fn test_parse_provider_errors() {
+        assert!(parse_provider("x", "status: OK\n").is_err());
+        assert!(parse_provider("x", "---\nstatus: FINE\n---\n").is_err());
+        assert!(
+            parse_provider("x", "---\nstatus: OK\nconfig_defaults:\n  bogus: 1\n---\n").is_err()
+        );
+    }
+}
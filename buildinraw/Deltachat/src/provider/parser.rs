@@ -0,0 +1,910 @@
+//! Parser for upstream `src/provider/data.rs`.
+//!
+//! `data.rs` is generated from the provider-db and only uses a small subset
+//! of Rust: `static` items holding struct literals, arrays, tuples, paths,
+//! string and integer literals, and a few calls such as `ProviderOptions::new()`,
+//! `Lazy::new(|| ...)` and `HashMap::from([...])`. The parser understands this
+//! subset generically, so field order, formatting and the wrapping of
+//! `PROVIDER_DATA` may change between revisions.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::{bail, Context as _, Result};
+
+use super::{
+    ConfigDefault, Oauth2Authorizer, Protocol, Provider, ProviderDb, ProviderOptions, Server,
+    Socket, Status, UsernamePattern,
+};
+use crate::config::Config;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Int(u64),
+    Lifetime,
+    Punct(char),
+}
+
+/// Expression from the Rust subset used by `data.rs`.
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Str(String),
+    Int(u64),
+    Bool(bool),
+    Path(Vec<String>),
+    Array(Vec<Value>),
+    Tuple(Vec<Value>),
+    Call(Vec<String>, Vec<Value>),
+    Method(Box<Value>, String, Vec<Value>),
+    Struct {
+        path: Vec<String>,
+        fields: Vec<(String, Value)>,
+        base: Option<Box<Value>>,
+    },
+}
+
+impl Value {
+    fn last_segment(&self) -> Option<&str> {
+        match self {
+            Value::Path(path) | Value::Call(path, _) | Value::Struct { path, .. } => {
+                path.last().map(String::as_str)
+            }
+            _ => None,
+        }
+    }
+
+    /// Strips wrappers that do not change the value, such as `.unwrap()`,
+    /// `.to_string()` or `Lazy::new(|| ...)`.
+    fn unwrapped(&self) -> &Value {
+        match self {
+            Value::Method(recv, name, args)
+                if args.is_empty() && matches!(name.as_str(), "unwrap" | "to_string" | "into") =>
+            {
+                recv.unwrapped()
+            }
+            Value::Call(path, args)
+                if args.len() == 1 && path.last().is_some_and(|s| s == "new") =>
+            {
+                if path.len() > 1 && path[path.len() - 2] == "Lazy" {
+                    args[0].unwrapped()
+                } else {
+                    self
+                }
+            }
+            _ => self,
+        }
+    }
+
+    /// Returns the first value in a depth-first walk for which `f` is true.
+    fn find(&self, f: &dyn Fn(&Value) -> bool) -> Option<&Value> {
+        if f(self) {
+            return Some(self);
+        }
+        match self {
+            Value::Array(items) | Value::Tuple(items) | Value::Call(_, items) => {
+                items.iter().find_map(|v| v.find(f))
+            }
+            Value::Method(recv, _, args) => {
+                recv.find(f).or_else(|| args.iter().find_map(|v| v.find(f)))
+            }
+            Value::Struct { fields, base, .. } => fields
+                .iter()
+                .find_map(|(_, v)| v.find(f))
+                .or_else(|| base.as_ref().and_then(|v| v.find(f))),
+            _ => None,
+        }
+    }
+}
+
+struct Lexer<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+    line: usize,
+}
+
+impl Lexer<'_> {
+    fn tokenize(src: &str) -> Result<Vec<(Token, usize)>> {
+        let mut lexer = Lexer {
+            chars: src.chars().peekable(),
+            line: 1,
+        };
+        let mut tokens = Vec::new();
+        while let Some(token) = lexer.next_token()? {
+            tokens.push((token, lexer.line));
+        }
+        Ok(tokens)
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.chars.next();
+        if c == Some('\n') {
+            self.line += 1;
+        }
+        c
+    }
+
+    fn next_token(&mut self) -> Result<Option<Token>> {
+        loop {
+            let Some(&c) = self.chars.peek() else {
+                return Ok(None);
+            };
+            if c.is_whitespace() {
+                self.bump();
+                continue;
+            }
+            if c == '/' {
+                self.bump();
+                match self.chars.peek() {
+                    Some('/') => {
+                        while self.chars.peek().is_some_and(|&c| c != '\n') {
+                            self.bump();
+                        }
+                    }
+                    Some('*') => {
+                        self.bump();
+                        let mut prev = ' ';
+                        loop {
+                            let Some(c) = self.bump() else {
+                                bail!("line {}: unterminated block comment", self.line);
+                            };
+                            if prev == '*' && c == '/' {
+                                break;
+                            }
+                            prev = c;
+                        }
+                    }
+                    _ => return Ok(Some(Token::Punct('/'))),
+                }
+                continue;
+            }
+            if c == '"' {
+                self.bump();
+                return self.string().map(Some);
+            }
+            if c == 'r' {
+                let mut ahead = self.chars.clone();
+                ahead.next();
+                // `r"..."` or `r#"..."#`, but not a raw identifier like `r#type`.
+                if ahead.find(|&c| c != '#') == Some('"') {
+                    self.bump();
+                    return self.raw_string().map(Some);
+                }
+            }
+            if c == '\'' {
+                self.bump();
+                while self
+                    .chars
+                    .peek()
+                    .is_some_and(|c| c.is_alphanumeric() || *c == '_')
+                {
+                    self.bump();
+                }
+                return Ok(Some(Token::Lifetime));
+            }
+            if c.is_ascii_digit() {
+                let mut digits = String::new();
+                while let Some(&c) = self.chars.peek() {
+                    if c.is_ascii_alphanumeric() || c == '_' {
+                        digits.push(c);
+                        self.bump();
+                    } else {
+                        break;
+                    }
+                }
+                let number: String = digits
+                    .chars()
+                    .take_while(|c| c.is_ascii_digit() || *c == '_')
+                    .filter(|c| *c != '_')
+                    .collect();
+                let value = number
+                    .parse()
+                    .with_context(|| format!("line {}: bad integer {digits:?}", self.line))?;
+                return Ok(Some(Token::Int(value)));
+            }
+            if c.is_alphabetic() || c == '_' {
+                let mut ident = String::new();
+                while let Some(&c) = self.chars.peek() {
+                    if c.is_alphanumeric() || c == '_' {
+                        ident.push(c);
+                        self.bump();
+                    } else {
+                        break;
+                    }
+                }
+                return Ok(Some(Token::Ident(ident)));
+            }
+            self.bump();
+            return Ok(Some(Token::Punct(c)));
+        }
+    }
+
+    fn string(&mut self) -> Result<Token> {
+        let mut s = String::new();
+        loop {
+            let Some(c) = self.bump() else {
+                bail!("line {}: unterminated string literal", self.line);
+            };
+            match c {
+                '"' => return Ok(Token::Str(s)),
+                '\\' => match self.bump() {
+                    Some('n') => s.push('\n'),
+                    Some('r') => s.push('\r'),
+                    Some('t') => s.push('\t'),
+                    Some('0') => s.push('\0'),
+                    Some('\n') => {
+                        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+                            self.bump();
+                        }
+                    }
+                    Some('u') => {
+                        let mut hex = String::new();
+                        while let Some(c) = self.bump() {
+                            match c {
+                                '{' => {}
+                                '}' => break,
+                                c => hex.push(c),
+                            }
+                        }
+                        let c = u32::from_str_radix(&hex, 16)
+                            .ok()
+                            .and_then(char::from_u32)
+                            .with_context(|| format!("line {}: bad unicode escape", self.line))?;
+                        s.push(c);
+                    }
+                    Some(c) => s.push(c),
+                    None => bail!("line {}: unterminated string literal", self.line),
+                },
+                c => s.push(c),
+            }
+        }
+    }
+
+    fn raw_string(&mut self) -> Result<Token> {
+        let mut hashes = 0;
+        while self.chars.peek() == Some(&'#') {
+            self.bump();
+            hashes += 1;
+        }
+        if self.bump() != Some('"') {
+            bail!("line {}: malformed raw string literal", self.line);
+        }
+        let mut s = String::new();
+        loop {
+            let Some(c) = self.bump() else {
+                bail!("line {}: unterminated raw string literal", self.line);
+            };
+            if c == '"' {
+                let mut ahead = self.chars.clone();
+                if (0..hashes).all(|_| ahead.next() == Some('#')) {
+                    for _ in 0..hashes {
+                        self.bump();
+                    }
+                    return Ok(Token::Str(s));
+                }
+            }
+            s.push(c);
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(t, _)| t)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.pos + offset).map(|(t, _)| t)
+    }
+
+    fn line(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .or(self.tokens.last())
+            .map_or(0, |(_, line)| *line)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).map(|(t, _)| t.clone());
+        self.pos += 1;
+        token
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(&Token::Punct(c)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<()> {
+        if !self.eat(c) {
+            bail!(
+                "line {}: expected `{c}`, found {:?}",
+                self.line(),
+                self.peek()
+            );
+        }
+        Ok(())
+    }
+
+    fn ident(&mut self) -> Result<String> {
+        match self.next() {
+            Some(Token::Ident(ident)) => Ok(ident),
+            other => bail!("line {}: expected identifier, found {other:?}", self.line()),
+        }
+    }
+
+    /// Collects all `static NAME: TYPE = VALUE;` items.
+    fn statics(&mut self) -> Result<Vec<(String, Value)>> {
+        let mut items = Vec::new();
+        while let Some(token) = self.next() {
+            if token != Token::Ident("static".to_string()) {
+                continue;
+            }
+            let name = self.ident()?;
+            self.expect(':')?;
+            self.skip_type()?;
+            let value = self.value()?;
+            self.expect(';')?;
+            items.push((name, value));
+        }
+        Ok(items)
+    }
+
+    /// Skips a type annotation up to and including the `=` that ends it.
+    fn skip_type(&mut self) -> Result<()> {
+        let mut depth = 0usize;
+        loop {
+            match self.next() {
+                Some(Token::Punct('=')) if depth == 0 => return Ok(()),
+                Some(Token::Punct('(' | '[' | '{')) => depth += 1,
+                Some(Token::Punct(')' | ']' | '}')) => depth = depth.saturating_sub(1),
+                Some(_) => {}
+                None => bail!("unexpected end of input in static type"),
+            }
+        }
+    }
+
+    fn list(&mut self, close: char) -> Result<Vec<Value>> {
+        let mut items = Vec::new();
+        while !self.eat(close) {
+            items.push(self.value()?);
+            if !self.eat(',') {
+                self.expect(close)?;
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    fn value(&mut self) -> Result<Value> {
+        let mut value = self.primary()?;
+        while self.peek() == Some(&Token::Punct('.'))
+            && matches!(self.peek_at(1), Some(Token::Ident(_)))
+        {
+            self.pos += 1;
+            let name = self.ident()?;
+            let args = if self.eat('(') {
+                self.list(')')?
+            } else {
+                Vec::new()
+            };
+            value = Value::Method(Box::new(value), name, args);
+        }
+        Ok(value)
+    }
+
+    fn primary(&mut self) -> Result<Value> {
+        let line = self.line();
+        match self.next() {
+            Some(Token::Punct('&' | '*')) => self.value(),
+            Some(Token::Punct('|')) => {
+                // Closure, e.g. `Lazy::new(|| ...)`; only the body matters.
+                while !self.eat('|') {
+                    if self.next().is_none() {
+                        bail!("line {line}: unterminated closure parameters");
+                    }
+                }
+                self.value()
+            }
+            Some(Token::Punct('[')) => Ok(Value::Array(self.list(']')?)),
+            Some(Token::Punct('(')) => {
+                let mut items = self.list(')')?;
+                if items.len() == 1 {
+                    Ok(items.remove(0))
+                } else {
+                    Ok(Value::Tuple(items))
+                }
+            }
+            Some(Token::Punct('{')) => {
+                let value = self.value()?;
+                self.expect('}')?;
+                Ok(value)
+            }
+            Some(Token::Str(s)) => Ok(Value::Str(s)),
+            Some(Token::Int(i)) => Ok(Value::Int(i)),
+            Some(Token::Ident(ident)) if ident == "true" || ident == "false" => {
+                Ok(Value::Bool(ident == "true"))
+            }
+            Some(Token::Ident(ident)) => self.path_expr(ident),
+            other => bail!("line {line}: unexpected {other:?}"),
+        }
+    }
+
+    fn path_expr(&mut self, first: String) -> Result<Value> {
+        let mut path = vec![first];
+        while self.peek() == Some(&Token::Punct(':')) && self.peek_at(1) == Some(&Token::Punct(':'))
+        {
+            self.pos += 2;
+            if self.eat('<') {
+                // Turbofish, e.g. `HashMap::<&str, &Provider>::from`.
+                let mut depth = 1;
+                while depth > 0 {
+                    match self.next() {
+                        Some(Token::Punct('<')) => depth += 1,
+                        Some(Token::Punct('>')) => depth -= 1,
+                        Some(_) => {}
+                        None => bail!("unexpected end of input in turbofish"),
+                    }
+                }
+                continue;
+            }
+            path.push(self.ident()?);
+        }
+
+        if self.eat('!') {
+            // Macro call such as `vec![...]`.
+            return match self.next() {
+                Some(Token::Punct('[')) => Ok(Value::Array(self.list(']')?)),
+                Some(Token::Punct('(')) => Ok(Value::Array(self.list(')')?)),
+                other => bail!(
+                    "line {}: unsupported macro delimiter {other:?}",
+                    self.line()
+                ),
+            };
+        }
+        if self.eat('(') {
+            return Ok(Value::Call(path, self.list(')')?));
+        }
+        if self.peek() == Some(&Token::Punct('{')) && path.last().is_some_and(|s| starts_upper(s)) {
+            self.pos += 1;
+            return self.struct_body(path);
+        }
+        Ok(Value::Path(path))
+    }
+
+    fn struct_body(&mut self, path: Vec<String>) -> Result<Value> {
+        let mut fields = Vec::new();
+        let mut base = None;
+        while !self.eat('}') {
+            if self.eat('.') {
+                self.expect('.')?;
+                base = Some(Box::new(self.value()?));
+            } else {
+                let name = self.ident()?;
+                self.expect(':')?;
+                fields.push((name, self.value()?));
+            }
+            if !self.eat(',') {
+                self.expect('}')?;
+                break;
+            }
+        }
+        Ok(Value::Struct { path, fields, base })
+    }
+}
+
+fn starts_upper(s: &str) -> bool {
+    s.chars().next().is_some_and(char::is_uppercase)
+}
+
+/// Converts `CamelCase` enum variant names to the `snake_case` config key.
+fn snake_case(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.extend(c.to_lowercase());
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+fn to_str(value: &Value, what: &str) -> Result<String> {
+    match value.unwrapped() {
+        Value::Str(s) => Ok(s.clone()),
+        other => bail!("{what}: expected string, found {other:?}"),
+    }
+}
+
+fn to_int<T: TryFrom<u64>>(value: &Value, what: &str) -> Result<T> {
+    match value.unwrapped() {
+        Value::Int(i) => T::try_from(*i)
+            .ok()
+            .with_context(|| format!("{what}: {i} out of range")),
+        other => bail!("{what}: expected integer, found {other:?}"),
+    }
+}
+
+fn to_bool(value: &Value, what: &str) -> Result<bool> {
+    match value.unwrapped() {
+        Value::Bool(b) => Ok(*b),
+        other => bail!("{what}: expected bool, found {other:?}"),
+    }
+}
+
+/// Parses a path such as `Socket::Ssl` or a bare `Ssl` into an enum.
+fn to_enum<T: FromStr>(value: &Value, what: &str) -> Result<T> {
+    match value.unwrapped() {
+        Value::Path(path) => {
+            let name = path.last().map(String::as_str).unwrap_or_default();
+            T::from_str(name)
+                .ok()
+                .with_context(|| format!("{what}: unknown variant {name:?}"))
+        }
+        other => bail!("{what}: expected enum variant, found {other:?}"),
+    }
+}
+
+/// Parses `None`/`Some(...)`.
+fn to_option<T>(
+    value: &Value,
+    what: &str,
+    f: impl FnOnce(&Value, &str) -> Result<T>,
+) -> Result<Option<T>> {
+    match value.unwrapped() {
+        Value::Path(path) if path.last().is_some_and(|s| s == "None") => Ok(None),
+        Value::Call(path, args) if path.last().is_some_and(|s| s == "Some") && args.len() == 1 => {
+            f(&args[0], what).map(Some)
+        }
+        other => bail!("{what}: expected option, found {other:?}"),
+    }
+}
+
+fn to_array<'a>(value: &'a Value, what: &str) -> Result<&'a [Value]> {
+    match value.unwrapped() {
+        Value::Array(items) => Ok(items),
+        other => bail!("{what}: expected array, found {other:?}"),
+    }
+}
+
+/// Fields of a struct literal.
+type Fields<'a> = &'a [(String, Value)];
+
+fn struct_fields<'a>(value: &'a Value, name: &str) -> Result<(Fields<'a>, Option<&'a Value>)> {
+    match value.unwrapped() {
+        Value::Struct { path, fields, base } if path.last().is_some_and(|s| s == name) => {
+            Ok((fields, base.as_deref()))
+        }
+        other => bail!("expected `{name} {{ ... }}`, found {other:?}"),
+    }
+}
+
+fn to_server(value: &Value) -> Result<Server> {
+    let (fields, _) = struct_fields(value, "Server")?;
+    let mut protocol = None;
+    let mut socket = Socket::default();
+    let mut hostname = None;
+    let mut port = None;
+    let mut username_pattern = UsernamePattern::Email;
+    for (name, value) in fields {
+        match name.as_str() {
+            "protocol" => protocol = Some(to_enum::<Protocol>(value, name)?),
+            "socket" => socket = to_enum(value, name)?,
+            "hostname" => hostname = Some(to_str(value, name)?),
+            "port" => port = Some(to_int(value, name)?),
+            "username_pattern" => username_pattern = to_enum(value, name)?,
+            _ => bail!("unknown Server field {name:?}"),
+        }
+    }
+    Ok(Server {
+        protocol: protocol.context("server without protocol")?,
+        socket,
+        hostname: hostname.context("server without hostname")?,
+        port: port.context("server without port")?,
+        username_pattern,
+    })
+}
+
+fn to_config_default(value: &Value) -> Result<ConfigDefault> {
+    let (fields, _) = struct_fields(value, "ConfigDefault")?;
+    let mut key = None;
+    let mut val = None;
+    for (name, value) in fields {
+        match name.as_str() {
+            "key" => {
+                let Value::Path(path) = value.unwrapped() else {
+                    bail!("key: expected config key, found {value:?}");
+                };
+                let variant = path.last().map(String::as_str).unwrap_or_default();
+                key = Some(
+                    Config::from_str(&snake_case(variant))
+                        .ok()
+                        .with_context(|| format!("unknown config key {variant:?}"))?,
+                );
+            }
+            "value" => val = Some(to_str(value, name)?),
+            _ => bail!("unknown ConfigDefault field {name:?}"),
+        }
+    }
+    Ok(ConfigDefault {
+        key: key.context("config default without key")?,
+        value: val.context("config default without value")?,
+    })
+}
+
+/// Applies `ProviderOptions` fields. Older revisions kept them directly in
+/// `Provider`, so this is also used for provider fields.
+fn apply_option(opt: &mut ProviderOptions, name: &str, value: &Value) -> Result<bool> {
+    match name {
+        "strict_tls" => opt.strict_tls = to_bool(value, name)?,
+        "max_smtp_rcpt_to" => opt.max_smtp_rcpt_to = to_option(value, name, to_int)?,
+        "delete_to_trash" => opt.delete_to_trash = to_bool(value, name)?,
+        _ => return Ok(false),
+    }
+    Ok(true)
+}
+
+fn to_options(value: &Value) -> Result<ProviderOptions> {
+    if value.last_segment() == Some("new") || value.last_segment() == Some("default") {
+        return Ok(ProviderOptions::new());
+    }
+    let (fields, base) = struct_fields(value, "ProviderOptions")?;
+    let mut opt = match base {
+        Some(base) => to_options(base)?,
+        None => ProviderOptions::new(),
+    };
+    for (name, value) in fields {
+        if !apply_option(&mut opt, name, value)? {
+            bail!("unknown ProviderOptions field {name:?}");
+        }
+    }
+    Ok(opt)
+}
+
+fn to_provider(value: &Value) -> Result<Provider> {
+    let (fields, _) = struct_fields(value, "Provider")?;
+    let mut provider = Provider {
+        id: String::new(),
+        status: Status::Ok,
+        before_login_hint: String::new(),
+        after_login_hint: String::new(),
+        overview_page: String::new(),
+        server: Vec::new(),
+        config_defaults: None,
+        oauth2_authorizer: None,
+        opt: ProviderOptions::new(),
+    };
+    for (name, value) in fields {
+        match name.as_str() {
+            "id" => provider.id = to_str(value, name)?,
+            "status" => provider.status = to_enum(value, name)?,
+            "before_login_hint" => provider.before_login_hint = to_str(value, name)?,
+            "after_login_hint" => provider.after_login_hint = to_str(value, name)?,
+            "overview_page" => provider.overview_page = to_str(value, name)?,
+            "server" => {
+                provider.server = to_array(value, name)?
+                    .iter()
+                    .map(to_server)
+                    .collect::<Result<_>>()?
+            }
+            "config_defaults" => {
+                provider.config_defaults = to_option(value, name, |v, what| {
+                    to_array(v, what)?.iter().map(to_config_default).collect()
+                })?
+            }
+            "oauth2_authorizer" => {
+                provider.oauth2_authorizer = to_option(value, name, to_enum::<Oauth2Authorizer>)?
+            }
+            "opt" => provider.opt = to_options(value)?,
+            name => {
+                if !apply_option(&mut provider.opt, name, value)? {
+                    bail!("unknown Provider field {name:?}");
+                }
+            }
+        }
+    }
+    if provider.id.is_empty() {
+        bail!("provider without id");
+    }
+    Ok(provider)
+}
+
+/// Reads `("domain", &P_NAME)` pairs from `PROVIDER_DATA` or `PROVIDER_IDS`.
+fn to_pairs(value: &Value, what: &str) -> Result<Vec<(String, String)>> {
+    let is_pair = |v: &Value| {
+        matches!(v, Value::Tuple(items) if items.len() == 2
+            && matches!(items[0], Value::Str(_))
+            && matches!(items[1], Value::Path(_)))
+    };
+    let items = value
+        .find(&|v| matches!(v, Value::Array(items) if items.first().is_some_and(is_pair)))
+        .with_context(|| format!("{what}: no (key, provider) list found"))?;
+    let Value::Array(items) = items else {
+        unreachable!()
+    };
+    items
+        .iter()
+        .map(|item| match item {
+            Value::Tuple(pair) => match (&pair[0], &pair[1]) {
+                (Value::Str(key), Value::Path(path)) => Ok((key.clone(), path.join("::"))),
+                _ => bail!("{what}: malformed entry {item:?}"),
+            },
+            _ => bail!("{what}: malformed entry {item:?}"),
+        })
+        .collect()
+}
+
+/// Reads the date from `_PROVIDER_UPDATED`.
+fn to_date(value: &Value) -> Result<String> {
+    let call = value
+        .find(&|v| {
+            matches!(v, Value::Call(path, args) if args.len() == 3
+            && path.last().is_some_and(|s| s.starts_with("from_ymd")))
+        })
+        .context("_PROVIDER_UPDATED: no date found")?;
+    let Value::Call(_, args) = call else {
+        unreachable!()
+    };
+    let year: u32 = to_int(&args[0], "year")?;
+    let month: u32 = to_int(&args[1], "month")?;
+    let day: u32 = to_int(&args[2], "day")?;
+    Ok(format!("{year:04}-{month:02}-{day:02}"))
+}
+
+/// Parses `data.rs` source into a provider database.
+pub(super) fn parse(src: &str) -> Result<ProviderDb> {
+    let tokens = Lexer::tokenize(src)?;
+    let statics = Parser { tokens, pos: 0 }.statics()?;
+
+    let mut providers = Vec::new();
+    let mut by_static = HashMap::new();
+    let mut data = None;
+    let mut ids = None;
+    let mut updated = None;
+    for (name, value) in &statics {
+        match name.as_str() {
+            "PROVIDER_DATA" => data = Some(to_pairs(value, name)?),
+            "PROVIDER_IDS" => ids = Some(to_pairs(value, name)?),
+            "_PROVIDER_UPDATED" | "PROVIDER_UPDATED" => updated = Some(to_date(value)?),
+            _ => {
+                let Some(literal) =
+                    value.find(&|v| matches!(v, Value::Struct { path, .. } if path.last().is_some_and(|s| s == "Provider")))
+                else {
+                    continue;
+                };
+                let provider = to_provider(literal).with_context(|| format!("in {name}"))?;
+                by_static.insert(name.clone(), provider.id.clone());
+                providers.push(provider);
+            }
+        }
+    }
+
+    let resolve = |(key, name): (String, String)| match by_static.get(&name) {
+        Some(id) => Ok((key, id.clone())),
+        None => bail!("{key:?} refers to unknown provider {name}"),
+    };
+    let data = data
+        .context("PROVIDER_DATA not found")?
+        .into_iter()
+        .map(resolve)
+        .collect::<Result<Vec<_>>>()?;
+    for (key, id) in ids
+        .into_iter()
+        .flatten()
+        .map(resolve)
+        .collect::<Result<Vec<_>>>()?
+    {
+        if key != id {
+            bail!("PROVIDER_IDS maps {key:?} to provider with id {id:?}");
+        }
+    }
+
+    ProviderDb::new(providers, data, updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bundled() {
+        let db = ProviderDb::bundled();
+        assert_eq!(db.provider_data().count(), 318);
+        assert_eq!(db.updated(), Some("2024-02-05"));
+
+        let provider = db.get_provider_by_id("gmail").unwrap();
+        assert_eq!(provider.oauth2_authorizer, Some(Oauth2Authorizer::Gmail));
+        assert!(provider.opt.delete_to_trash);
+        assert!(provider
+            .before_login_hint
+            .contains("\"2-Step Verification\""));
+
+        let provider = db.get_provider_by_id("testrun").unwrap();
+        let defaults = provider.config_defaults.as_ref().unwrap();
+        assert!(defaults
+            .iter()
+            .any(|d| d.key == Config::BccSelf && d.value == "1"));
+    }
+
+    #[test]
+    fn test_parse_old_revision() {
+        // Older revisions wrapped providers in `Lazy`, used `vec![]` and kept
+        // the options directly in `Provider`.
+        let src = r#"
+            static P_EXAMPLE: Lazy<Provider> = Lazy::new(|| Provider {
+                id: "example",
+                status: Status::Broken,
+                before_login_hint: "say \"hi\"",
+                after_login_hint: "",
+                overview_page: "https://providers.delta.chat/example",
+                server: vec![Server { protocol: Imap, socket: Plain, hostname: "imap.example.org", port: 143, username_pattern: Emaillocalpart }],
+                config_defaults: None,
+                strict_tls: false,
+                max_smtp_rcpt_to: Some(50),
+                oauth2_authorizer: None,
+            });
+            pub static PROVIDER_DATA: Lazy<HashMap<&'static str, &'static Provider>> =
+                Lazy::new(|| [("example.org", &*P_EXAMPLE)].iter().copied().collect());
+        "#;
+        let db = ProviderDb::from_data_rs(src).unwrap();
+        let provider = db.get_provider_by_domain("example.org").unwrap();
+        assert_eq!(provider.status, Status::Broken);
+        assert_eq!(provider.before_login_hint, "say \"hi\"");
+        assert_eq!(provider.server[0].socket, Socket::Plain);
+        assert_eq!(
+            provider.server[0].username_pattern,
+            UsernamePattern::Emaillocalpart
+        );
+        assert!(!provider.opt.strict_tls);
+        assert_eq!(provider.opt.max_smtp_rcpt_to, Some(50));
+        assert_eq!(db.updated(), None);
+    }
+
+    #[test]
+    fn test_parse_unknown_config_key() {
+        let src = r#"
+            static P_X: Provider = Provider {
+                id: "x", status: Status::Ok, before_login_hint: "", after_login_hint: "",
+                overview_page: "", server: &[], opt: ProviderOptions::new(),
+                config_defaults: Some(&[ConfigDefault { key: Config::NoSuchKey, value: "1" }]),
+                oauth2_authorizer: None,
+            };
+            pub(crate) static PROVIDER_DATA: [(&str, &Provider); 1] = [("x.org", &P_X)];
+        "#;
+        assert!(ProviderDb::from_data_rs(src).is_err());
+    }
+
+    #[test]
+    fn test_parse_unknown_field() {
+        let provider = |extra: &str| {
+            format!(
+                r#"
+                static P_X: Provider = Provider {{
+                    id: "x", status: Status::Ok, before_login_hint: "", after_login_hint: "",
+                    overview_page: "", server: &[], config_defaults: None,
+                    oauth2_authorizer: None, {extra}
+                }};
+                pub(crate) static PROVIDER_DATA: [(&str, &Provider); 1] = [("x.org", &P_X)];
+            "#
+            )
+        };
+        assert!(ProviderDb::from_data_rs(&provider("strict_tls: true,")).is_ok());
+        let err = ProviderDb::from_data_rs(&provider("new_field: true,")).unwrap_err();
+        assert!(format!("{err:#}").contains(r#"unknown Provider field "new_field""#));
+        let opt = "opt: ProviderOptions { strict_tls: true, new_option: 1 },";
+        assert!(ProviderDb::from_data_rs(&provider(opt)).is_err());
+    }
+}
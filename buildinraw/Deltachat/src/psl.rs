@@ -0,0 +1,201 @@
+//! Public Suffix List.
+//!
+//! The bundled `public_suffix_list.dat` is the 2023-02-09 snapshot of
+//! <https://publicsuffix.org/list/public_suffix_list.dat>. Like `tldextract`,
+//! which `autoconfig.py` uses, only the ICANN section is applied by default,
+//! so `blogspot.com` is a registered domain rather than a public suffix.
+//!
+//! Domains are compared in their ASCII form, see [`idn`](crate::idn).
+
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use crate::idn;
+
+const BUNDLED_LIST: &str = include_str!("public_suffix_list.dat");
+
+/// Marker line starting the private section.
+const PRIVATE_MARKER: &str = "// ===BEGIN PRIVATE DOMAINS===";
+
+static BUNDLED: LazyLock<SuffixList> = LazyLock::new(|| SuffixList::parse(BUNDLED_LIST, false));
+
+/// Kind of a rule, keyed by the labels it names.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Rule {
+    /// `co.uk`: the name is a public suffix.
+    Normal,
+
+    /// `*.ck`: every child of the name is a public suffix.
+    Wildcard,
+
+    /// `!www.ck`: the name is not a public suffix despite a wildcard.
+    Exception,
+}
+
+/// Parsed Public Suffix List.
+#[derive(Debug, Clone, Default)]
+pub struct SuffixList {
+    /// Rules by name; a name can carry both a normal and a wildcard rule.
+    rules: HashMap<String, Vec<Rule>>,
+}
+
+impl SuffixList {
+    /// Parses a list in `public_suffix_list.dat` format, with or without
+    /// its private section.
+    pub fn parse(src: &str, private: bool) -> Self {
+        let mut list = Self::default();
+        for line in src.lines() {
+            let line = line.trim();
+            if line == PRIVATE_MARKER && !private {
+                break;
+            }
+            // Rules end at the first whitespace.
+            let Some(rule) = line.split_whitespace().next() else {
+                continue;
+            };
+            if rule.starts_with("//") {
+                continue;
+            }
+            let (name, kind) = if let Some(name) = rule.strip_prefix('!') {
+                (name, Rule::Exception)
+            } else if let Some(name) = rule.strip_prefix("*.") {
+                (name, Rule::Wildcard)
+            } else {
+                (rule, Rule::Normal)
+            };
+            list.rules
+                .entry(idn::normalize(name))
+                .or_default()
+                .push(kind);
+        }
+        list
+    }
+
+    /// The bundled snapshot, ICANN section only.
+    pub fn bundled() -> &'static Self {
+        &BUNDLED
+    }
+
+    fn has(&self, name: &str, kind: Rule) -> bool {
+        self.rules
+            .get(name)
+            .is_some_and(|kinds| kinds.contains(&kind))
+    }
+
+    /// Public suffix of a domain, `co.uk` for `mail.example.co.uk`.
+    ///
+    /// Unlisted top-level domains are public suffixes themselves, as the
+    /// implicit `*` rule of the list requires.
+    pub fn public_suffix(&self, domain: &str) -> String {
+        let domain = idn::normalize(domain);
+        // Suffixes of the domain, longest first, so that the first matching
+        // rule is the prevailing one.
+        let suffixes: Vec<&str> = suffixes(&domain).collect();
+        for (i, suffix) in suffixes.iter().enumerate() {
+            if self.has(suffix, Rule::Exception) {
+                return suffixes.get(i + 1).unwrap_or(suffix).to_string();
+            }
+            if self.has(suffix, Rule::Normal) {
+                return suffix.to_string();
+            }
+            if let Some(parent) = suffixes.get(i + 1) {
+                if self.has(parent, Rule::Wildcard) {
+                    return suffix.to_string();
+                }
+            }
+        }
+        suffixes.last().copied().unwrap_or_default().to_string()
+    }
+
+    /// Registered domain of a domain, `example.co.uk` for
+    /// `mail.example.co.uk`: its public suffix plus one label.
+    ///
+    /// `None` if the domain is a public suffix itself.
+    pub fn registered_domain(&self, domain: &str) -> Option<String> {
+        let domain = idn::normalize(domain);
+        let suffix = self.public_suffix(&domain);
+        suffixes(&domain)
+            .take_while(|s| s.len() > suffix.len())
+            .last()
+            .map(str::to_string)
+    }
+
+    /// Parent domains of a domain up to and including its registered domain,
+    /// nearest first: `sub.example.co.uk` and `example.co.uk` for
+    /// `mail.sub.example.co.uk`.
+    pub fn parent_domains(&self, domain: &str) -> Vec<String> {
+        let domain = idn::normalize(domain);
+        let Some(registered) = self.registered_domain(&domain) else {
+            return Vec::new();
+        };
+        suffixes(&domain)
+            .skip(1)
+            .take_while(|s| s.len() >= registered.len())
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// The domain and each of its parents, longest first.
+fn suffixes(domain: &str) -> impl Iterator<Item = &str> {
+    let starts = domain.match_indices('.').map(|(i, _)| i + 1);
+    std::iter::once(domain)
+        .chain(starts.map(move |i| &domain[i..]))
+        .filter(|s| !s.is_empty())
+}
+
+/// [`SuffixList::registered_domain`] with the bundled list.
+pub fn registered_domain(domain: &str) -> Option<String> {
+    SuffixList::bundled().registered_domain(domain)
+}
+
+/// [`SuffixList::parent_domains`] with the bundled list.
+pub fn parent_domains(domain: &str) -> Vec<String> {
+    SuffixList::bundled().parent_domains(domain)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registered_domain() {
+        let cases = [
+            ("mail.sub.example.co.uk", Some("example.co.uk")),
+            ("Example.COM.", Some("example.com")),
+            ("imap.mail.yahoo.co.jp", Some("yahoo.co.jp")),
+            ("foo.blogspot.com", Some("blogspot.com")),
+            ("www.ck", Some("www.ck")),
+            ("mail.example.ck", Some("mail.example.ck")),
+            ("city.kawasaki.jp", Some("city.kawasaki.jp")),
+            ("mail.bücher.de", Some("xn--bcher-kva.de")),
+            ("example.unlisted", Some("example.unlisted")),
+            ("co.uk", None),
+            ("example.ck", None),
+            ("com", None),
+        ];
+        for (domain, expected) in cases {
+            assert_eq!(registered_domain(domain).as_deref(), expected, "{domain}");
+        }
+    }
+
+    #[test]
+    fn test_parent_domains() {
+        assert_eq!(
+            parent_domains("mail.sub.example.co.uk"),
+            ["sub.example.co.uk", "example.co.uk"]
+        );
+        assert!(parent_domains("example.co.uk").is_empty());
+        assert!(parent_domains("co.uk").is_empty());
+    }
+
+    #[test]
+    fn test_private_section() {
+        let list = SuffixList::parse(BUNDLED_LIST, true);
+        assert_eq!(list.public_suffix("foo.blogspot.com"), "blogspot.com");
+        assert_eq!(
+            list.registered_domain("a.foo.blogspot.com").as_deref(),
+            Some("foo.blogspot.com")
+        );
+    }
+}
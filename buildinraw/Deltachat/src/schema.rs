@@ -0,0 +1,557 @@
+//! Normalized schema for built-in list entries.
+//!
+//! Every line of a list under `buildinlists/` is one JSON-serialized
+//! [`Entry`], whatever client the list comes from:
+//!
+//! ```json
+//! {"schema_version":2,"source":"DeltaChat","source_revision":"2024-02-05",
+//!  "domain":"163.com",
+//!  "incoming_servers":[{"protocol":"Imap","hostname":"imap.163.com","port":993,
+//!                       "socket_type":"Ssl","authentication":["password-cleartext"]}],
+//!  "outgoing_servers":[{"protocol":"Smtp","hostname":"smtp.163.com","port":465,
+//!                       "socket_type":"Ssl","authentication":["password-cleartext"]}]}
+//! ```
+//!
+//! Fields that are empty (`domain_regular`, `mx_regular`, `provider`,
+//! `source_revision`, `oauth2`) are omitted. [`SCHEMA_VERSION`] is bumped whenever a
+//! field or enum value changes meaning, so consumers can reject lines they do
+//! not understand. Lines written by the older per-client converters can still
+//! be read with [`Entry::from_legacy`].
+//!
+//! Versions:
+//!
+//! 1. Initial schema.
+//! 2. `Jmap`, `Ews` and `ActiveSync` protocols; `NTLM`, `GSSAPI` and
+//!    `TLS-client-cert` authentication, `password-encrypted` meaning
+//!    CRAM-MD5, and Delta Chat servers listing the methods Delta Chat uses.
+
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Context as _, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::buildin::Source;
+use crate::provider::{Direction, Protocol, Socket};
+
+/// Version of the schema written by this crate.
+pub const SCHEMA_VERSION: u32 = 2;
+
+/// Authentication method a list advertises for a server.
+///
+/// Values are written in the vocabulary of Thunderbird's `config-v1.1.xml`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Authentication {
+    /// Password sent as is, e.g. `AUTH PLAIN`, `AUTH LOGIN` or IMAP `LOGIN`.
+    #[serde(rename = "password-cleartext")]
+    PasswordCleartext,
+
+    /// Password hashed with a challenge, `CRAM-MD5`.
+    #[serde(rename = "password-encrypted")]
+    CramMd5,
+
+    /// NTLM, Microsoft's Secure Password Authentication.
+    #[serde(rename = "NTLM")]
+    Ntlm,
+
+    /// Kerberos through GSSAPI.
+    #[serde(rename = "GSSAPI")]
+    Gssapi,
+
+    /// OAuth 2 bearer token.
+    #[serde(rename = "OAuth2")]
+    OAuth2,
+
+    /// TLS client certificate, e.g. with SASL `EXTERNAL`.
+    #[serde(rename = "TLS-client-cert")]
+    ClientCert,
+
+    /// No authentication.
+    #[serde(rename = "none")]
+    None,
+}
+
+impl Authentication {
+    /// Value of an `<authentication>` element of Thunderbird's
+    /// `config-v1.1.xml`.
+    pub fn thunderbird_value(self) -> &'static str {
+        match self {
+            Self::PasswordCleartext => "password-cleartext",
+            Self::CramMd5 => "password-encrypted",
+            Self::Ntlm => "NTLM",
+            Self::Gssapi => "GSSAPI",
+            Self::OAuth2 => "OAuth2",
+            Self::ClientCert => "TLS-client-cert",
+            Self::None => "none",
+        }
+    }
+
+    /// Parses an `<authentication>` value of Thunderbird's
+    /// `config-v1.1.xml`, including the older `plain` and `secure`.
+    ///
+    /// `client-IP-address` and `smtp-after-pop` authorize the client without
+    /// credentials on the connection and count as [`None`](Self::None).
+    pub fn from_thunderbird(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "password-cleartext" | "plain" => Some(Self::PasswordCleartext),
+            "password-encrypted" | "secure" => Some(Self::CramMd5),
+            "ntlm" => Some(Self::Ntlm),
+            "gssapi" => Some(Self::Gssapi),
+            "oauth2" => Some(Self::OAuth2),
+            "tls-client-cert" => Some(Self::ClientCert),
+            "none" | "client-ip-address" | "smtp-after-pop" => Some(Self::None),
+            _ => None,
+        }
+    }
+
+    /// Parses a `<proto>_authentication` value of Mailspring, which uses the
+    /// Thunderbird vocabulary.
+    pub fn from_mailspring(value: &str) -> Option<Self> {
+        Self::from_thunderbird(value)
+    }
+
+    /// Parses a Nodemailer `authMethod`, a SASL mechanism name.
+    pub fn from_nodemailer(auth_method: &str) -> Option<Self> {
+        match auth_method.trim().to_ascii_uppercase().as_str() {
+            "PLAIN" | "LOGIN" => Some(Self::PasswordCleartext),
+            "CRAM-MD5" => Some(Self::CramMd5),
+            "NTLM" => Some(Self::Ntlm),
+            "GSSAPI" => Some(Self::Gssapi),
+            "XOAUTH2" | "OAUTHBEARER" => Some(Self::OAuth2),
+            "EXTERNAL" => Some(Self::ClientCert),
+            _ => None,
+        }
+    }
+
+    /// Parses the `SPA` element of an Autodiscover POX `Protocol`: `on`
+    /// means NTLM, `off` the password sent as is.
+    pub fn from_autodiscover_spa(spa: &str) -> Option<Self> {
+        match spa.trim().to_ascii_lowercase().as_str() {
+            "on" => Some(Self::Ntlm),
+            "off" => Some(Self::PasswordCleartext),
+            _ => None,
+        }
+    }
+
+    /// Parses a method as written by any of the client lists, for lines in
+    /// the legacy format.
+    pub fn from_list_value(value: &str) -> Option<Self> {
+        Self::from_thunderbird(value).or_else(|| Self::from_nodemailer(value))
+    }
+}
+
+/// Mail server of an entry.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MailServer {
+    /// Server protocol, e.g. SMTP or IMAP.
+    pub protocol: Protocol,
+
+    /// Server host.
+    pub hostname: String,
+
+    /// Server port.
+    pub port: u16,
+
+    /// Port security, e.g. TLS or STARTTLS.
+    pub socket_type: Socket,
+
+    /// Authentication methods, empty if the list does not say.
+    #[serde(default)]
+    pub authentication: Vec<Authentication>,
+
+    /// Login name as a Thunderbird placeholder, `%EMAILADDRESS%` or
+    /// `%EMAILLOCALPART%`, if the list says.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+}
+
+/// OAuth 2 authorization a client list configures for a provider.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OAuth2Descriptor {
+    /// URL the user is sent to for authorizing the client.
+    pub authorization_endpoint: String,
+
+    /// URL exchanging the authorization code for tokens.
+    pub token_endpoint: String,
+
+    /// Scopes requested, in list order.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+
+    /// Client id registered by the client with the provider.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+
+    /// Redirect URI registered with the provider, if the list fixes it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub redirect_uri: Option<String>,
+
+    /// Further parameters of the authorization request, e.g.
+    /// `access_type=offline`.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub parameters: BTreeMap<String, String>,
+}
+
+/// One line of a built-in list.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Entry {
+    /// Always [`SCHEMA_VERSION`] when written by this crate.
+    pub schema_version: u32,
+
+    /// Client list the entry comes from.
+    pub source: Source,
+
+    /// Revision of the client list: the `_PROVIDER_UPDATED` date for Delta
+    /// Chat, the upstream commit for the other lists.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_revision: Option<String>,
+
+    /// Domain the configuration applies to.
+    pub domain: String,
+
+    /// Domain with U-labels, if it is internationalized.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub domain_unicode: Option<String>,
+
+    /// Regular expressions matching further domains.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub domain_regular: Vec<String>,
+
+    /// Regular expressions matching MX hostnames.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub mx_regular: Vec<String>,
+
+    /// Provider name, if the list keys entries by name rather than domain.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider: Option<String>,
+
+    /// Servers to receive mail from.
+    pub incoming_servers: Vec<MailServer>,
+
+    /// Servers to send mail through.
+    pub outgoing_servers: Vec<MailServer>,
+
+    /// OAuth 2 authorization, if the list configures it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oauth2: Option<OAuth2Descriptor>,
+}
+
+impl Entry {
+    /// Creates an entry without servers.
+    pub fn new(source: Source, domain: impl Into<String>) -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            source,
+            source_revision: None,
+            domain: domain.into(),
+            domain_unicode: None,
+            domain_regular: Vec::new(),
+            mx_regular: Vec::new(),
+            provider: None,
+            incoming_servers: Vec::new(),
+            outgoing_servers: Vec::new(),
+            oauth2: None,
+        }
+    }
+
+    /// All servers, incoming first.
+    pub fn servers(&self) -> impl Iterator<Item = &MailServer> {
+        self.incoming_servers.iter().chain(&self.outgoing_servers)
+    }
+
+    /// Parses a JSON line in this schema.
+    ///
+    /// The version is checked before the rest of the line, so lines of a
+    /// newer schema are reported as such even if they hold values this
+    /// version does not know.
+    pub fn from_json(line: &str) -> Result<Self> {
+        #[derive(Deserialize)]
+        struct Versioned {
+            schema_version: u32,
+        }
+
+        let Versioned { schema_version } = serde_json::from_str(line)?;
+        if schema_version > SCHEMA_VERSION {
+            bail!("unsupported schema version {schema_version}");
+        }
+        Ok(serde_json::from_str(line)?)
+    }
+
+    /// Serializes the entry as a JSON line.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("entry is serializable")
+    }
+
+    /// Parses a line as written by the per-client converters before the
+    /// schema existed, e.g. `{"domain": ..., "FairEmail": {"incomingServers": ...}}`.
+    ///
+    /// Both the camelCase (`incomingServers`, `socketType`) and the lowercase
+    /// (`incomingservers`, `sockettype`) spellings are accepted, with ports
+    /// as numbers or strings. Servers without a usable port are dropped.
+    pub fn from_legacy(source: Source, line: &str) -> Result<Self> {
+        let item: Value = serde_json::from_str(line)?;
+        let domain = item
+            .get("domain")
+            .and_then(Value::as_str)
+            .context("missing domain")?;
+        let config = item
+            .get(source.key())
+            .with_context(|| format!("missing {:?}", source.key()))?;
+        let strings = |key: &str| -> Vec<String> {
+            item.get(key)
+                .and_then(Value::as_array)
+                .map(|a| {
+                    a.iter()
+                        .filter_map(Value::as_str)
+                        .map(str::to_string)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+
+        let mut entry = Entry::new(source, domain);
+        entry.domain_regular = strings("domain_regular");
+        entry.mx_regular = strings("mx_regular");
+        entry.provider = item
+            .get("provider")
+            .and_then(Value::as_str)
+            .map(str::to_string);
+        // The old Delta Chat converter listed every non-IMAP server as
+        // outgoing, so servers are sorted by the direction of their protocol.
+        let servers = legacy_servers(config, "incomingServers")?
+            .into_iter()
+            .chain(legacy_servers(config, "outgoingServers")?);
+        for server in servers {
+            match server.protocol.direction() {
+                Direction::Incoming => entry.incoming_servers.push(server),
+                Direction::Outgoing => entry.outgoing_servers.push(server),
+            }
+        }
+        Ok(entry)
+    }
+}
+
+/// Gets a field by its camelCase name or its lowercase spelling.
+fn legacy_field<'a>(value: &'a Value, name: &str) -> Option<&'a Value> {
+    value
+        .get(name)
+        .or_else(|| value.get(name.to_ascii_lowercase()))
+}
+
+fn legacy_servers(config: &Value, name: &str) -> Result<Vec<MailServer>> {
+    let Some(servers) = legacy_field(config, name).and_then(Value::as_array) else {
+        return Ok(Vec::new());
+    };
+    let mut out = Vec::new();
+    for server in servers {
+        let text = |name: &str| {
+            legacy_field(server, name)
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+        };
+        let port = match legacy_field(server, "port") {
+            Some(Value::Number(n)) => n.as_u64().and_then(|p| u16::try_from(p).ok()),
+            Some(Value::String(s)) => s.trim().parse().ok(),
+            _ => None,
+        };
+        let Some(port) = port else {
+            continue;
+        };
+        let protocol = match text("type").to_ascii_lowercase().as_str() {
+            "imap" => Protocol::Imap,
+            "pop" | "pop3" => Protocol::Pop3,
+            "smtp" => Protocol::Smtp,
+            "jmap" => Protocol::Jmap,
+            "ews" | "exchange" => Protocol::Ews,
+            "activesync" | "eas" => Protocol::ActiveSync,
+            other => bail!("unknown server type {other:?}"),
+        };
+        let socket_type = match text("socketType").to_ascii_lowercase().as_str() {
+            "ssl" => Socket::Ssl,
+            "starttls" => Socket::Starttls,
+            "plain" => Socket::Plain,
+            _ => Socket::Automatic,
+        };
+        let authentication = match legacy_field(server, "authentication") {
+            Some(Value::Array(methods)) => methods
+                .iter()
+                .filter_map(Value::as_str)
+                .filter_map(Authentication::from_list_value)
+                .collect(),
+            Some(Value::String(method)) => method
+                .split(',')
+                .filter_map(Authentication::from_list_value)
+                .collect(),
+            _ => Vec::new(),
+        };
+        out.push(MailServer {
+            protocol,
+            hostname: text("hostname").to_string(),
+            port,
+            socket_type,
+            authentication,
+            username: None,
+        });
+    }
+    Ok(out)
+}
+
+/// Reads a list in either the current schema or the legacy format.
+pub fn read_list(source: Source, src: &str) -> Result<Vec<Entry>> {
+    src.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let entry = if line.contains("\"schema_version\"") {
+                Entry::from_json(line)
+            } else {
+                Entry::from_legacy(source, line)
+            };
+            entry.with_context(|| format!("line {}", i + 1))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Entry {
+        let mut entry = Entry::new(Source::Mailcore, "pobox");
+        entry.source_revision = Some("7417b2e8dd7e2c028aadb72056e4d1428c0627c4".to_string());
+        entry.domain_regular = vec![r"pobox\.com".to_string()];
+        entry.mx_regular = vec![r"mx-1\.pobox\.com".to_string()];
+        entry.incoming_servers.push(MailServer {
+            protocol: Protocol::Pop3,
+            hostname: "mail.pobox.com".to_string(),
+            port: 995,
+            socket_type: Socket::Ssl,
+            authentication: vec![Authentication::OAuth2, Authentication::PasswordCleartext],
+            username: Some("%EMAILLOCALPART%".to_string()),
+        });
+        entry.outgoing_servers.push(MailServer {
+            protocol: Protocol::Smtp,
+            hostname: "smtp.pobox.com".to_string(),
+            port: 587,
+            socket_type: Socket::Starttls,
+            authentication: Vec::new(),
+            username: None,
+        });
+        entry
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let entry = sample();
+        let json = entry.to_json();
+        assert_eq!(Entry::from_json(&json).unwrap(), entry);
+        assert!(json.contains(r#""port":995"#));
+        assert!(json.contains(r#""authentication":["OAuth2","password-cleartext"]"#));
+
+        let mut minimal = Entry::new(Source::Nodemailer, "gmail.com");
+        minimal.outgoing_servers = entry.outgoing_servers.clone();
+        let json = minimal.to_json();
+        assert!(!json.contains("domain_regular"));
+        assert!(!json.contains("source_revision"));
+        assert_eq!(Entry::from_json(&json).unwrap(), minimal);
+    }
+
+    #[test]
+    fn test_future_version_rejected() {
+        let json = sample().to_json().replace(
+            &format!(r#""schema_version":{SCHEMA_VERSION}"#),
+            &format!(r#""schema_version":{}"#, SCHEMA_VERSION + 1),
+        );
+        let err = Entry::from_json(&json).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!("unsupported schema version {}", SCHEMA_VERSION + 1)
+        );
+
+        // Unknown values of a newer schema do not hide its version.
+        let json = json.replace(r#""protocol":"Pop3""#, r#""protocol":"Future""#);
+        assert!(json.contains("Future"));
+        assert!(Entry::from_json(&json)
+            .unwrap_err()
+            .to_string()
+            .starts_with("unsupported schema version"));
+    }
+
+    #[test]
+    fn test_read_committed_lists() {
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../../buildinlists");
+        for source in Source::ALL {
+            let src = std::fs::read_to_string(dir.join(source.list_file())).unwrap();
+            let entries = read_list(source, &src).unwrap();
+            assert!(!entries.is_empty(), "{source:?}");
+            for entry in entries {
+                assert_eq!(Entry::from_json(&entry.to_json()).unwrap(), entry);
+            }
+        }
+    }
+
+    #[test]
+    fn test_authentication_vocabularies() {
+        use Authentication::*;
+        assert_eq!(
+            Authentication::from_thunderbird("password-encrypted"),
+            Some(CramMd5)
+        );
+        assert_eq!(Authentication::from_thunderbird("NTLM"), Some(Ntlm));
+        assert_eq!(
+            Authentication::from_thunderbird("client-IP-address"),
+            Some(None)
+        );
+        assert_eq!(
+            Authentication::from_mailspring("plain"),
+            Some(PasswordCleartext)
+        );
+        assert_eq!(
+            Authentication::from_nodemailer("LOGIN"),
+            Some(PasswordCleartext)
+        );
+        assert_eq!(Authentication::from_nodemailer("XOAUTH2"), Some(OAuth2));
+        assert_eq!(Authentication::from_autodiscover_spa("on"), Some(Ntlm));
+        assert_eq!(Authentication::from_list_value("cram-md5"), Some(CramMd5));
+        assert_eq!(Authentication::from_list_value("kerberos"), Option::None);
+
+        for method in [
+            PasswordCleartext,
+            CramMd5,
+            Ntlm,
+            Gssapi,
+            OAuth2,
+            ClientCert,
+            None,
+        ] {
+            let json = serde_json::to_string(&method).unwrap();
+            assert_eq!(json, format!("{:?}", method.thunderbird_value()));
+            assert_eq!(
+                Authentication::from_thunderbird(method.thunderbird_value()),
+                Some(method)
+            );
+        }
+    }
+
+    #[test]
+    fn test_from_legacy() {
+        let lowercase = r#"{"domain":"163.com","DeltaChat":{"incomingservers":[{"type":"Imap","hostname":"imap.163.com","port":993,"sockettype":"Ssl","authentication":""}],"outgoingservers":[]}}"#;
+        let entry = Entry::from_legacy(Source::DeltaChat, lowercase).unwrap();
+        assert_eq!(entry.incoming_servers[0].port, 993);
+        assert_eq!(entry.incoming_servers[0].socket_type, Socket::Ssl);
+
+        let camel = r#"{"domain": "mail.ru", "mailspring": {"incomingServers": [{"type": "imap", "hostname": "imap.mail.ru", "port": "993", "socketType": "ssl", "authentication": ["OAuth2", "password-cleartext"]}], "outgoingServers": [{"type": "smtp", "hostname": "", "port": "", "socketType": "plain", "authentication": ""}]}}"#;
+        let entry = Entry::from_legacy(Source::Mailspring, camel).unwrap();
+        assert_eq!(entry.incoming_servers[0].port, 993);
+        assert_eq!(
+            entry.incoming_servers[0].authentication,
+            vec![Authentication::OAuth2, Authentication::PasswordCleartext]
+        );
+        assert!(entry.outgoing_servers.is_empty());
+
+        let pop3_as_outgoing = r#"{"domain":"x.org","DeltaChat":{"incomingServers":[],"outgoingServers":[{"type":"Pop3","hostname":"pop.x.org","port":995,"socketType":"Ssl"},{"type":"Smtp","hostname":"smtp.x.org","port":465,"socketType":"Ssl"}]}}"#;
+        let entry = Entry::from_legacy(Source::DeltaChat, pop3_as_outgoing).unwrap();
+        assert_eq!(entry.incoming_servers[0].protocol, Protocol::Pop3);
+        assert_eq!(entry.outgoing_servers.len(), 1);
+    }
+}
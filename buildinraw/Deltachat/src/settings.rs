@@ -0,0 +1,192 @@
+//! Effective client settings per provider.
+//!
+//! Delta Chat applies a provider's `config_defaults` on top of the built-in
+//! defaults of [`Config`]. The [`Matrix`] shows the result for every
+//! provider, so providers silently changing client behaviour, e.g. turning
+//! off `e2ee_enabled`, stand out.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+
+use anyhow::Result;
+use serde::Serialize;
+use strum::IntoEnumIterator;
+
+use crate::config::{Config, ConfigValue};
+use crate::provider::{Provider, ProviderDb};
+
+/// Effective value of a key for one provider.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Setting {
+    pub value: ConfigValue,
+
+    /// Whether the value comes from the provider's `config_defaults` rather
+    /// than the built-in default.
+    pub overridden: bool,
+}
+
+/// Effective values of the keys that have a built-in default or are
+/// overridden by the provider.
+///
+/// # Panics
+///
+/// If a provider value does not parse; [`ProviderDb`] validates them when
+/// loading.
+pub fn effective(provider: &Provider) -> BTreeMap<Config, Setting> {
+    let mut settings: BTreeMap<Config, Setting> = Config::iter()
+        .filter_map(|key| {
+            let value = key.default_value()?;
+            Some((
+                key,
+                Setting {
+                    value,
+                    overridden: false,
+                },
+            ))
+        })
+        .collect();
+    for default in provider.config_defaults.iter().flatten() {
+        let value = default.typed_value().expect("validated on load");
+        settings.insert(
+            default.key,
+            Setting {
+                value,
+                overridden: true,
+            },
+        );
+    }
+    settings
+}
+
+/// Effective settings of one provider.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Row {
+    pub id: String,
+    pub settings: BTreeMap<Config, Setting>,
+}
+
+impl Row {
+    /// Whether the provider overrides any built-in default.
+    pub fn overrides_any(&self) -> bool {
+        self.settings.values().any(|s| s.overridden)
+    }
+}
+
+/// Providers × keys matrix of effective settings.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct Matrix {
+    /// Keys with a built-in default or a provider override, in `Config`
+    /// order. Other keys have no value for any provider.
+    pub keys: Vec<Config>,
+
+    /// One row per provider, in definition order.
+    pub rows: Vec<Row>,
+}
+
+impl Matrix {
+    pub fn new(db: &ProviderDb) -> Self {
+        let rows: Vec<Row> = db
+            .providers()
+            .iter()
+            .map(|provider| Row {
+                id: provider.id.clone(),
+                settings: effective(provider),
+            })
+            .collect();
+        let keys = Config::iter()
+            .filter(|key| rows.iter().any(|row| row.settings.contains_key(key)))
+            .collect();
+        Self { keys, rows }
+    }
+
+    /// Writes the matrix as CSV with a header row of keys. Overridden values
+    /// are marked with a trailing `*`.
+    pub fn write_csv(&self, out: &mut dyn Write) -> Result<()> {
+        write!(out, "provider")?;
+        for key in &self.keys {
+            write!(out, ",{key}")?;
+        }
+        writeln!(out)?;
+        for row in &self.rows {
+            write!(out, "{}", csv_field(&row.id))?;
+            for key in &self.keys {
+                let cell = match row.settings.get(key) {
+                    Some(s) if s.overridden => format!("{}*", s.value),
+                    Some(s) => s.value.to_string(),
+                    None => String::new(),
+                };
+                write!(out, ",{}", csv_field(&cell))?;
+            }
+            writeln!(out)?;
+        }
+        Ok(())
+    }
+}
+
+/// Quotes a CSV field if needed.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::ShowEmails;
+
+    #[test]
+    fn test_effective() {
+        let db = ProviderDb::bundled();
+        let testrun = effective(db.get_provider_by_id("testrun").unwrap());
+        assert_eq!(
+            testrun[&Config::MvboxMove],
+            Setting {
+                value: ConfigValue::Bool(false),
+                overridden: true,
+            }
+        );
+        assert_eq!(
+            testrun[&Config::ShowEmails].value.as_show_emails(),
+            Some(ShowEmails::All)
+        );
+        assert!(!testrun[&Config::ShowEmails].overridden);
+        assert!(!testrun.contains_key(&Config::Addr));
+    }
+
+    #[test]
+    fn test_matrix_csv() {
+        let db = ProviderDb::bundled();
+        let matrix = Matrix::new(&db);
+        assert_eq!(matrix.rows.len(), db.providers().len());
+        assert!(matrix.keys.contains(&Config::BccSelf));
+        assert!(matrix
+            .rows
+            .iter()
+            .any(|row| row.id == "testrun" && row.overrides_any()));
+
+        let mut out = Vec::new();
+        matrix.write_csv(&mut out).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let header: Vec<&str> = csv.lines().next().unwrap().split(',').collect();
+        let column = header.iter().position(|h| *h == "mvbox_move").unwrap();
+        let testrun = csv.lines().find(|l| l.starts_with("testrun,")).unwrap();
+        assert_eq!(testrun.split(',').nth(column), Some("0*"));
+
+        let json = serde_json::to_value(&matrix).unwrap();
+        assert!(json["keys"]
+            .as_array()
+            .unwrap()
+            .contains(&"mvbox_move".into()));
+        let testrun = json["rows"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|row| row["id"] == "testrun")
+            .unwrap();
+        assert_eq!(testrun["settings"]["mvbox_move"]["value"], "0");
+        assert_eq!(testrun["settings"]["show_emails"]["value"], "2");
+    }
+}